@@ -1,6 +1,6 @@
 use amethyst::{
-    assets::Loader,
-    audio::{OggFormat, SourceHandle, AudioSink},
+    assets::ProgressCounter,
+    audio::{SourceHandle, AudioSink},
     ecs::{World, WorldExt},
 };
 
@@ -11,68 +11,109 @@ use amethyst::{
 
 use std::{iter::Cycle, vec::IntoIter};
 
-const LASER_SOUND: &str = "audio/laser.ogg";
-const THRUST_SOUND: &str = "audio/short_thrust.ogg";
-const IMPACT_SOUND: &str = "audio/impact.ogg";
-//const EXPLOSION_SOUND: &str = "audio/explosion.ogg";
+use crate::resources::{cached_sound, AssetError, AssetErrors, AssetKind, AssetManifest, AssetScope};
 
-const MUSIC_TRACKS: &[&str] = &[
-    "audio/thrust_sequence.ogg",
-    "audio/raining_bits.ogg",
+// asset manifest ids
+const LASER_SOUND: &str = "laser";
+const THRUST_SOUND: &str = "thrust";
+const IMPACT_SOUND: &str = "impact";
+//const EXPLOSION_SOUND: &str = "explosion";
+
+pub const SOUND_EFFECTS: &[&str] = &[LASER_SOUND, THRUST_SOUND, IMPACT_SOUND];
+
+pub const MUSIC_TRACKS: &[&str] = &[
+    "thrust_sequence",
 ];
 
+// each is None if its sound is missing, so it stays silent
+#[derive(Default)]
 pub struct Sounds {
-    pub laser_sfx: SourceHandle,
-    pub thrust_sfx: SourceHandle,
-    pub impact_sfx: SourceHandle,
-    //pub explosion_sfx: SourceHandle,
-    //pub score_sfx: SourceHandle,
+    pub laser_sfx: Option<SourceHandle>,
+    pub thrust_sfx: Option<SourceHandle>,
+    pub impact_sfx: Option<SourceHandle>,
+    //pub explosion_sfx: Option<SourceHandle>,
+    //pub score_sfx: Option<SourceHandle>,
 }
 
 pub struct Music {
     pub music: Cycle<IntoIter<SourceHandle>>,
 }
 
-/// Loads an ogg audio track
-fn load_audio_track(loader: &Loader, world: &World, file: &str) -> SourceHandle {
-    loader.load(file, OggFormat, (), &world.read_resource())
+/// Loads the ogg track with the given asset manifest id through the AssetCache.
+/// Returns None, reporting why, if the id is unknown or its file has already failed to load.
+fn load_sound(
+    world: &mut World,
+    id: &str,
+    scope: AssetScope,
+    progress_counter: &mut ProgressCounter,
+) -> Option<SourceHandle> {
+    world.entry::<AssetErrors>().or_insert_with(AssetErrors::default);
+
+    let path = match world.read_resource::<AssetManifest>().sound(id) {
+        Some(path) => path.to_string(),
+        None => {
+            world.write_resource::<AssetErrors>().report(
+                AssetError::UnknownId { kind: AssetKind::Sound, id: id.to_string() });
+            return None;
+        }
+    };
+
+    if world.read_resource::<AssetErrors>().failed(&path) {
+        return None;
+    }
+    Some(cached_sound(world, &path, scope, progress_counter))
 }
 
-/// Initialize audio in the world.
-pub fn initialize_audio(world: &mut World) {
-    let (sound_effects, music) = {
-        let loader = world.read_resource::<Loader>();
+/// Loads the sound effects into the world, and the default playlist into the AssetCache.
+/// Tracks come from the cache, so this only reads from disk the first time it is called.
+pub fn initialize_audio(world: &mut World, progress_counter: &mut ProgressCounter) {
+    if world.has_value::<Sounds>() {
+        return;
+    }
 
-        let mut sink = world.write_resource::<AudioSink>();
+    world.write_resource::<AudioSink>().set_volume(0.25);
 
-        sink.set_volume(0.25);
+    let sound = Sounds {
+        laser_sfx: load_sound(world, LASER_SOUND, AssetScope::Global, progress_counter),
+        thrust_sfx: load_sound(world, THRUST_SOUND, AssetScope::Global, progress_counter),
+        impact_sfx: load_sound(world, IMPACT_SOUND, AssetScope::Global, progress_counter),
+        //explosion_sfx: load_sound(world, EXPLOSION_SOUND, AssetScope::Global, progress_counter),
+        //score_sfx: load_sound(world, SCORE_SOUND, AssetScope::Global, progress_counter),
+    };
 
-        let music = MUSIC_TRACKS.iter()
-            .map(|file| load_audio_track(&loader, &world, file))
-            .collect::<Vec<_>>()
-            .into_iter()
-            .cycle();
+    for id in MUSIC_TRACKS {
+        load_sound(world, id, AssetScope::Global, progress_counter);
+    }
 
-        let music = Music { music };
+    world.insert(sound);
+}
 
-        let sound = Sounds {
-            laser_sfx: load_audio_track(&loader, &world, LASER_SOUND),
-            thrust_sfx: load_audio_track(&loader, &world, THRUST_SOUND),
-            impact_sfx: load_audio_track(&loader, &world, IMPACT_SOUND),
-            //explosion_sfx: load_audio_track(&loader, &world, EXPLOSION_SOUND),
-            //score_sfx: load_audio_track(&loader, &world, SCORE_SOUND),
-        };
+/// Restarts the default music playlist, leaving out any track that failed to load
+pub fn play_default_music(world: &mut World) {
+    let music = MUSIC_TRACKS.iter()
+        .filter_map(|id| load_sound(world, id, AssetScope::Global, &mut ProgressCounter::new()))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .cycle();
 
-        (sound, music)
-    };
-    // add sound effects to world in another scope
-    world.insert(sound_effects);
-    world.insert(music);
+    world.insert(Music { music });
+}
+
+/// Loads an arena's music track into the AssetCache, held only for the match
+pub fn load_music_track(world: &mut World, id: &str, progress_counter: &mut ProgressCounter) {
+    load_sound(world, id, AssetScope::Arena, progress_counter);
+}
+
+/// Replaces the music playlist with a single looping track, held only for the match.
+/// If the track cannot be loaded the match is played in silence.
+pub fn play_music_track(world: &mut World, id: &str) {
+    let track = load_sound(world, id, AssetScope::Arena, &mut ProgressCounter::new());
+    world.insert(Music { music: track.into_iter().collect::<Vec<_>>().into_iter().cycle() });
 }
 
 pub fn play_laser_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
     if let Some(ref output) = output.as_ref() {
-        if let Some(sound) = storage.get(&sounds.laser_sfx) {
+        if let Some(sound) = sounds.laser_sfx.as_ref().and_then(|sfx| storage.get(sfx)) {
             output.play_once(sound, 1.0)
         }
     }
@@ -80,7 +121,7 @@ pub fn play_laser_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output:
 
 pub fn play_thrust_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
     if let Some(ref output) = output.as_ref() {
-        if let Some(sound) = storage.get(&sounds.thrust_sfx) {
+        if let Some(sound) = sounds.thrust_sfx.as_ref().and_then(|sfx| storage.get(sfx)) {
             output.play_once(sound, 1.0)
         }
     }
@@ -88,7 +129,7 @@ pub fn play_thrust_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output
 
 pub fn play_impact_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
     if let Some(ref output) = output.as_ref() {
-        if let Some(sound) = storage.get(&sounds.impact_sfx) {
+        if let Some(sound) = sounds.impact_sfx.as_ref().and_then(|sfx| storage.get(sfx)) {
             output.play_once(sound, 1.0)
         }
     }
@@ -0,0 +1,147 @@
+use amethyst::{
+    assets::ProgressCounter,
+    core::{transform::Transform, math},
+    ecs::prelude::{Component, DenseVecStorage, NullStorage},
+    prelude::*,
+    renderer::{SpriteRender, Transparent, resources::Tint, palette::Srgba},
+};
+
+use crate::resources::{ArenaDefinition, BackgroundDefinition, EdgeMode, SpriteSheetList, SPRITE_PIXEL,
+    AssetErrors, AssetManifest, AssetScope, cached_sprite_sheet};
+use super::planet::{initialise_planets, initialise_obstacles};
+use super::asteroid::initialise_asteroids;
+use super::starfield::{Parallax, initialise_starfield};
+
+/// Marks the sprite drawn behind an arena
+#[derive(Default)]
+pub struct ArenaBackground;
+
+impl Component for ArenaBackground {
+    type Storage = NullStorage<Self>;
+}
+
+/// DangerZone tracks how far the danger zone border has closed in this round
+#[derive(Debug, Default)]
+pub struct DangerZone {
+    pub elapsed: f32,
+    pub margin: f32,
+}
+
+/// Which side of the arena a danger zone border strip covers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edge {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+/// DangerZoneBorder is a translucent strip drawn over the part of the arena outside the danger zone
+#[derive(Debug)]
+pub struct DangerZoneBorder {
+    pub edge: Edge,
+}
+
+impl Component for DangerZoneBorder {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Makes `arena` the current arena and creates its background and hazards.
+/// Ships are placed separately by `initialise_ships`, which reads the spawn points from it.
+pub fn initialise_arena(world: &mut World, arena: ArenaDefinition) {
+    log::info!("Entering arena {}", arena.name);
+
+    if let Some(background) = arena.background.as_ref() {
+        initialise_background(world, &arena, background);
+    }
+
+    // the starfield is tiled to the arena's view, so the arena goes in before the bodies
+    world.insert(arena.clone());
+
+    initialise_starfield(world, &arena.starfield);
+
+    if let EdgeMode::DangerZone { .. } = arena.edge_mode {
+        initialise_danger_zone(world);
+    }
+
+    initialise_arena_bodies(world, arena);
+}
+
+/// Makes `arena` the current arena and creates what the ships can hit: its planets,
+/// obstacles and asteroids. The headless batch uses this on its own, without the backdrop.
+pub fn initialise_arena_bodies(world: &mut World, arena: ArenaDefinition) {
+    world.insert(arena.clone());
+    world.insert(DangerZone::default());
+
+    initialise_planets(world, &arena.planets);
+    initialise_obstacles(world, &arena.obstacles);
+
+    // asteroids are seeded last so they keep clear of the planets and obstacles
+    if let Some(field) = arena.asteroids.as_ref() {
+        initialise_asteroids(world, field);
+    }
+}
+
+fn initialise_background(world: &mut World, arena: &ArenaDefinition, background: &BackgroundDefinition) {
+    // an unknown background was reported by the LoadingState, so the arena goes without
+    let entry = match world.read_resource::<AssetManifest>().sprite_sheet(&background.sprite_sheet) {
+        Some(entry) => entry.clone(),
+        None => return,
+    };
+
+    // and so was one that failed to load
+    {
+        let errors = world.read_resource::<AssetErrors>();
+        if errors.failed(&entry.texture) || errors.failed(&entry.sprite_sheet) {
+            return;
+        }
+    }
+
+    // already loaded by the LoadingState before a match, but the title screen demo picks
+    // its arena on the spot and has it loaded here. Either way the cache lets it go when the match ends.
+    let sprite_sheet = cached_sprite_sheet(
+        world,
+        &entry.texture,
+        &entry.sprite_sheet,
+        AssetScope::Arena,
+        &mut ProgressCounter::new(),
+    );
+
+    let (x, y) = (arena.width / 2.0, arena.height / 2.0);
+
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(x, y, -0.9);
+    transform.set_scale(math::Vector3::new(background.scale, background.scale, background.scale));
+
+    world
+        .create_entity()
+        .with(ArenaBackground)
+        .with(Parallax { x, y, depth: background.depth, scale: background.scale, tile: None })
+        .with(SpriteRender {
+            sprite_sheet,
+            sprite_number: 0,
+        })
+        .with(transform)
+        .build();
+}
+
+/// Creates the four border strips, which the ArenaEdgeSystem sizes as the zone closes in
+fn initialise_danger_zone(world: &mut World) {
+    let sprite_render = world.read_resource::<SpriteSheetList>().render(SPRITE_PIXEL);
+
+    for edge in [Edge::Left, Edge::Right, Edge::Bottom, Edge::Top].iter() {
+        let mut transform = Transform::default();
+        // starts with no size until the zone begins to close
+        transform.set_scale(math::Vector3::new(0.0, 0.0, 1.0));
+        transform.set_translation_z(0.5);
+
+        world
+            .create_entity()
+            .with(DangerZoneBorder { edge: *edge })
+            .maybe_with(sprite_render.clone())
+            .with(transform)
+            .with(Tint(Srgba::new(1.0, 0.1, 0.1, 0.25)))
+            .with(Transparent)
+            .build();
+    }
+}
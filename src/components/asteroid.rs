@@ -0,0 +1,182 @@
+use amethyst::{
+    core::{transform::Transform, math},
+    ecs::prelude::{Component, DenseVecStorage, Entities, Join, LazyUpdate},
+    prelude::*,
+    renderer::{SpriteRender, SpriteSheet, Transparent},
+    assets::Handle,
+};
+
+use serde::{Serialize, Deserialize};
+
+use std::f32::consts::PI;
+
+use crate::resources::{SpriteSheetList, SPRITE_ASTEROID, ArenaDefinition};
+use crate::components::{Physical, GravityWell};
+use crate::states::RandomGen;
+
+// asteroid.png is 128 pixels across
+const ASTEROID_SPRITE_SIZE: f32 = 128.0;
+// mass per square pixel of radius, so a large asteroid outweighs a ship
+const ASTEROID_DENSITY: f32 = 0.05;
+// fastest an asteroid can spin, in radians per second
+const MAX_SPIN: f32 = 1.0;
+
+/// Asteroids smaller than this break into debris instead of splitting
+pub const MIN_SPLIT_RADIUS: f32 = 20.0;
+
+/// Asteroid is a drifting hazard that splits into smaller fragments when destroyed
+#[derive(Debug, Clone, Copy)]
+pub struct Asteroid {
+    pub structure: i32,
+    // damage dealt to a ship that rams it
+    pub damage: i32,
+    pub spin: f32,
+}
+
+impl Component for Asteroid {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Asteroid {
+    pub fn new(radius: f32, spin: f32) -> Asteroid {
+        Asteroid {
+            structure: radius as i32,
+            damage: (radius / 2.0) as i32,
+            spin,
+        }
+    }
+}
+
+/// AsteroidField sets how many asteroids drift through an arena and how large they are.
+/// While fewer than `min_count` remain, new asteroids appear in the field, clear of the ships and planets.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AsteroidField {
+    pub min_count: usize,
+    pub max_count: usize,
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub max_speed: f32,
+}
+
+impl AsteroidField {
+    /// A random radius within the field's size range
+    pub fn random_radius(&self, random_gen: &RandomGen) -> f32 {
+        self.min_radius + random_gen.next_f32() * (self.max_radius - self.min_radius)
+    }
+
+    /// A random drift velocity up to the field's max speed
+    pub fn random_velocity(&self, random_gen: &RandomGen) -> math::Vector2<f32> {
+        let angle = random_gen.next_f32() * 2.0 * PI;
+        let speed = random_gen.next_f32() * self.max_speed;
+        math::Vector2::new(angle.cos() * speed, angle.sin() * speed)
+    }
+}
+
+/// Random spin for a new asteroid, in either direction
+pub fn random_spin(random_gen: &RandomGen) -> f32 {
+    (random_gen.next_f32() * 2.0 - 1.0) * MAX_SPIN
+}
+
+/// Queues a new asteroid entity, used both when seeding the field and when splitting.
+/// Without a sprite sheet the asteroid is still there to hit, just unseen.
+pub fn spawn_asteroid(
+    entities: &Entities,
+    lazy_update: &LazyUpdate,
+    sprite_sheet: Option<Handle<SpriteSheet>>,
+    x: f32,
+    y: f32,
+    radius: f32,
+    velocity: math::Vector2<f32>,
+    spin: f32,
+) {
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(x, y, 0.0);
+    transform.rotate_2d(spin * PI);
+
+    let scale = radius * 2.0 / ASTEROID_SPRITE_SIZE;
+    transform.set_scale(math::Vector3::new(scale, scale, scale));
+
+    let mut physical = Physical::new(radius, radius * radius * ASTEROID_DENSITY, 0.0, 0.0);
+    physical.velocity = velocity;
+
+    lazy_update
+        .create_entity(entities)
+        .with(Asteroid::new(radius, spin))
+        .with(physical)
+        .with(transform)
+        .maybe_with(sprite_sheet.map(|sprite_sheet| SpriteRender {
+            sprite_sheet,
+            sprite_number: 0,
+        }))
+        .with(Transparent)
+        .build();
+}
+
+/// Picks a spot for an asteroid of `radius` in the middle of the arena, clear of everything
+/// in `keep_clear` (x, y and radius of each gravity well or ship). Returns None if no clear
+/// spot turned up, in which case the asteroid is left out rather than dropped onto something.
+pub fn random_position(
+    random_gen: &RandomGen,
+    width: f32,
+    height: f32,
+    radius: f32,
+    keep_clear: &[(f32, f32, f32)],
+) -> Option<(f32, f32)> {
+    // retry a few times rather than drop an asteroid onto a planet or a ship
+    for _ in 0..10 {
+        let position = (
+            width * (0.25 + random_gen.next_f32() * 0.5),
+            random_gen.next_f32() * height,
+        );
+
+        let clear = keep_clear.iter().all(|(other_x, other_y, other_radius)| {
+            let (dx, dy) = (position.0 - other_x, position.1 - other_y);
+            (dx * dx + dy * dy).sqrt() > other_radius + radius * 2.0
+        });
+
+        if clear {
+            return Some(position);
+        }
+    }
+    None
+}
+
+/// Seeds the asteroid field down the middle of the current arena, away from the ship spawns and planets
+pub fn initialise_asteroids(world: &mut World, field: &AsteroidField) {
+    let sprite_sheet = world.read_resource::<SpriteSheetList>().get(SPRITE_ASTEROID).cloned();
+
+    let wells: Vec<(f32, f32, f32)> = (&world.read_storage::<Transform>(), &world.read_storage::<GravityWell>()).join()
+        .map(|(transform, well)| (transform.translation().x, transform.translation().y, well.radius))
+        .collect();
+
+    let (width, height) = {
+        let arena = world.read_resource::<ArenaDefinition>();
+        (arena.width, arena.height)
+    };
+
+    let entities = world.entities();
+    let lazy_update = world.read_resource::<LazyUpdate>();
+    let random_gen = world.read_resource::<RandomGen>();
+
+    let spread = field.max_count.saturating_sub(field.min_count) + 1;
+    let count = field.min_count + random_gen.next_usize() % spread;
+
+    for _ in 0..count {
+        let radius = field.random_radius(&random_gen);
+        let position = match random_position(&random_gen, width, height, radius, &wells) {
+            Some(position) => position,
+            None => continue,
+        };
+
+        spawn_asteroid(
+            &entities,
+            &lazy_update,
+            sprite_sheet.clone(),
+            position.0,
+            position.1,
+            radius,
+            field.random_velocity(&random_gen),
+            random_spin(&random_gen),
+        );
+    }
+}
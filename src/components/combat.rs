@@ -1,9 +1,14 @@
 use amethyst::ecs::{DenseVecStorage, Component};
 
+use serde::{Serialize, Deserialize};
+
+use super::shield::Shield;
+
 /// Combat represents damage, defense and attack in the game
 #[derive(Debug)]
 pub struct Combat {
     pub structure: i32,
+    pub max_structure: i32,
     pub armour: i32,
     // lasers
     pub laser_damage: i32,
@@ -27,13 +32,54 @@ impl Component for Combat {
     type Storage = DenseVecStorage<Self>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub enum LaserType {
     Single,
     Dual,
 }
 
+/// The source of a hit, which decides how well shields hold up against it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageType {
+    Laser,
+    Collision,
+}
+
+/// DamageReport breaks a hit down into what each defensive layer stopped
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DamageReport {
+    pub raw: i32,
+    pub absorbed: i32,
+    pub blocked: i32,
+    pub dealt: i32,
+}
+
 impl Combat {
+    /// Applies a hit to the shield first, then armour, then structure
+    pub fn take_damage(
+        &mut self,
+        shield: Option<&mut Shield>,
+        damage: i32,
+        damage_type: DamageType,
+    ) -> DamageReport {
+        let absorbed = match shield {
+            Some(shield) => shield.absorb(damage, damage_type),
+            None => 0,
+        };
+
+        let remaining = damage - absorbed;
+        let dealt = (remaining - self.armour).max(0);
+
+        self.structure -= dealt;
+
+        DamageReport {
+            raw: damage,
+            absorbed,
+            blocked: remaining - dealt,
+            dealt,
+        }
+    }
+
     pub fn new(
         structure: i32,
         armour: i32, 
@@ -50,6 +96,7 @@ impl Combat {
     ) -> Combat {
             Combat {
                 structure,
+                max_structure: structure,
                 armour,
 
                 laser_damage,
@@ -70,3 +117,66 @@ impl Combat {
             }
         }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combat(structure: i32, armour: i32) -> Combat {
+        Combat::new(structure, armour, 20, 3.0, 10.0, LaserType::Single, 0.2, 25, 30.0, 6.0, 5.0)
+    }
+
+    fn shield(strength: f32) -> Shield {
+        let mut shield = Shield::new(30.0, 6.0, 3.0, 1.0, 0.25);
+        shield.strength = strength;
+        shield
+    }
+
+    #[test]
+    fn shield_absorbs_what_it_can_and_armour_blocks_the_rest() {
+        let mut combat = combat(150, 6);
+        let mut shield = shield(12.0);
+
+        let report = combat.take_damage(Some(&mut shield), 20, DamageType::Laser);
+
+        assert_eq!((report.raw, report.absorbed, report.blocked, report.dealt), (20, 12, 6, 2));
+        assert_eq!(combat.structure, 148);
+        assert!(shield.strength.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn shield_only_partly_absorbs_collisions() {
+        let mut combat = combat(150, 6);
+        let mut shield = shield(30.0);
+
+        let report = combat.take_damage(Some(&mut shield), 30, DamageType::Collision);
+
+        // a quarter of 30, rounded, is taken from the shield exactly as reported
+        assert_eq!((report.absorbed, report.blocked, report.dealt), (8, 6, 16));
+        assert!((shield.strength - 22.0).abs() < f32::EPSILON);
+        assert_eq!(combat.structure, 134);
+    }
+
+    #[test]
+    fn armour_can_block_a_whole_hit() {
+        let mut combat = combat(150, 6);
+
+        let report = combat.take_damage(None, 4, DamageType::Laser);
+
+        assert_eq!((report.absorbed, report.blocked, report.dealt), (0, 4, 0));
+        assert_eq!(combat.structure, 150);
+    }
+
+    #[test]
+    fn depleted_shield_lets_everything_through() {
+        let mut combat = combat(150, 6);
+        let mut shield = shield(0.4);
+
+        let report = combat.take_damage(Some(&mut shield), 20, DamageType::Laser);
+
+        // the fraction of a point left is kept, not drained for nothing
+        assert_eq!((report.absorbed, report.blocked, report.dealt), (0, 6, 14));
+        assert!((shield.strength - 0.4).abs() < f32::EPSILON);
+        assert_eq!(combat.structure, 136);
+    }
+}
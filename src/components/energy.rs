@@ -0,0 +1,104 @@
+use amethyst::ecs::{DenseVecStorage, Component};
+
+/// Energy is the battery that weapons, boosts and specials draw from
+#[derive(Debug, Clone, Copy)]
+pub struct Energy {
+    pub current: f32,
+    pub max: f32,
+    // energy per second
+    pub regen_rate: f32,
+
+    // cost per shot
+    pub laser_cost: f32,
+    // cost per second of boosted thrust
+    pub boost_cost: f32,
+    pub special_cost: f32,
+}
+
+impl Component for Energy {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Energy {
+    pub fn new(
+        max: f32,
+        regen_rate: f32,
+        laser_cost: f32,
+        boost_cost: f32,
+        special_cost: f32,
+    ) -> Energy {
+        Energy {
+            current: max,
+            max,
+            regen_rate,
+            laser_cost,
+            boost_cost,
+            special_cost,
+        }
+    }
+
+    /// Spends `amount` if there is enough energy, returning whether it was spent
+    pub fn try_consume(&mut self, amount: f32) -> bool {
+        if self.current >= amount {
+            self.current -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn regenerate(&mut self, delta_seconds: f32) {
+        self.current = (self.current + self.regen_rate * delta_seconds).min(self.max);
+    }
+
+    pub fn refill(&mut self) {
+        self.current = self.max;
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.current / self.max
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn energy() -> Energy {
+        Energy::new(40.0, 4.0, 2.0, 10.0, 12.0)
+    }
+
+    #[test]
+    fn exactly_enough_energy_is_spent() {
+        let mut energy = energy();
+        energy.current = 12.0;
+
+        assert!(energy.try_consume(energy.special_cost));
+        assert!(energy.current.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn too_little_energy_spends_nothing() {
+        let mut energy = energy();
+        energy.current = 1.5;
+
+        assert!(!energy.try_consume(energy.laser_cost));
+        assert!((energy.current - 1.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn regeneration_stops_at_max() {
+        let mut energy = energy();
+        energy.current = 30.0;
+
+        energy.regenerate(1.0);
+        assert!((energy.current - 34.0).abs() < f32::EPSILON);
+
+        energy.regenerate(10.0);
+        assert!((energy.current - energy.max).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,33 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+/// FloatingText is a UI label pinned to a spot in the arena, such as the damage number
+/// thrown up by a hit. It drifts upward and fades out over its lifetime.
+#[derive(Debug, Clone, Copy)]
+pub struct FloatingText {
+    pub x: f32,
+    pub y: f32,
+    pub age: f32,
+    pub lifetime: f32,
+    pub colour: [f32; 3],
+}
+
+impl Component for FloatingText {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl FloatingText {
+    pub fn new(x: f32, y: f32, lifetime: f32, colour: [f32; 3]) -> FloatingText {
+        FloatingText {
+            x,
+            y,
+            age: 0.0,
+            lifetime,
+            colour,
+        }
+    }
+
+    /// How far through its life the text is, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        (self.age / self.lifetime).min(1.0)
+    }
+}
@@ -0,0 +1,13 @@
+use amethyst::ecs::{Component, DenseVecStorage, Entity};
+
+/// Ghost is a copy of `source` drawn on the far side of a wrapping arena edge,
+/// so anything straddling the edge shows on both sides at once.
+/// Ghosts are drawn only, they have no physics and take no part in collisions.
+#[derive(Debug, Clone, Copy)]
+pub struct Ghost {
+    pub source: Entity,
+}
+
+impl Component for Ghost {
+    type Storage = DenseVecStorage<Self>;
+}
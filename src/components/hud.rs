@@ -0,0 +1,249 @@
+use amethyst::{
+    assets::Loader,
+    ecs::prelude::{Entities, Entity, WriteStorage},
+    prelude::*,
+    ui::{Anchor, FontHandle, TtfFormat, UiImage, UiText, UiTransform},
+};
+
+use super::ship::Side;
+use crate::resources::KILL_FEED_LENGTH;
+
+/// Full width of a bar in the HUD
+pub const HUD_BAR_WIDTH: f32 = 200.0;
+
+// gap between a panel and the side of the window
+const HUD_MARGIN: f32 = 50.0;
+// height of a player's panel, which stack upward once both bottom corners are taken
+const PANEL_HEIGHT: f32 = 110.0;
+// special ability readout, above the bars
+const SPECIAL_TEXT_Y: f32 = 100.0;
+// gap between neighbouring scores at the top of the window
+const SCORE_SPACING: f32 = 100.0;
+// how much of a bar's colour shows in its empty track
+const TRACK_SHADE: f32 = 0.25;
+// kill feed lines, down from the top left corner
+const KILL_FEED_LINE: f32 = 24.0;
+
+/// The bars each player has in the HUD, top to bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HudBarKind {
+    Structure,
+    Shield,
+    Energy,
+    Reload,
+}
+
+impl HudBarKind {
+    /// Height of the bar and its distance up from the bottom of its panel
+    fn layout(self) -> (f32, f32) {
+        match self {
+            HudBarKind::Structure => (16.0, 70.0),
+            HudBarKind::Shield => (8.0, 56.0),
+            HudBarKind::Energy => (8.0, 44.0),
+            HudBarKind::Reload => (5.0, 34.0),
+        }
+    }
+
+    pub fn colour(self, side: Side) -> [f32; 4] {
+        match (side, self) {
+            (Side::Light, HudBarKind::Structure) => [0.2, 0.9, 0.3, 1.0],
+            (Side::Light, HudBarKind::Shield) => [0.3, 0.8, 1.0, 1.0],
+            (Side::Light, HudBarKind::Energy) => [0.75, 1.0, 0.4, 1.0],
+            (Side::Light, HudBarKind::Reload) => [0.85, 1.0, 0.85, 1.0],
+            (Side::Dark, HudBarKind::Structure) => [0.9, 0.3, 0.2, 1.0],
+            (Side::Dark, HudBarKind::Shield) => [0.55, 0.5, 1.0, 1.0],
+            (Side::Dark, HudBarKind::Energy) => [1.0, 0.6, 0.3, 1.0],
+            (Side::Dark, HudBarKind::Reload) => [1.0, 0.85, 0.8, 1.0],
+        }
+    }
+}
+
+/// HudBar is a fill drawn over a dim track, shrinking toward the edge of the window.
+/// `shown` is the fraction last drawn, so the fill is only resized when it changes.
+#[derive(Debug, Clone, Copy)]
+pub struct HudBar {
+    pub track: Entity,
+    pub fill: Entity,
+    pub shown: f32,
+}
+
+/// PlayerHud is the panel of readouts following one ship
+#[derive(Debug, Clone)]
+pub struct PlayerHud {
+    pub ship: Entity,
+    pub side: Side,
+    pub structure: HudBar,
+    pub shield: HudBar,
+    pub energy: HudBar,
+    pub reload: HudBar,
+    pub special_text: Entity,
+    pub score_text: Entity,
+}
+
+impl PlayerHud {
+    pub fn bar_mut(&mut self, kind: HudBarKind) -> &mut HudBar {
+        match kind {
+            HudBarKind::Structure => &mut self.structure,
+            HudBarKind::Shield => &mut self.shield,
+            HudBarKind::Energy => &mut self.energy,
+            HudBarKind::Reload => &mut self.reload,
+        }
+    }
+
+    /// Every entity drawing the panel
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities = vec![self.special_text, self.score_text];
+        for bar in [&self.structure, &self.shield, &self.energy, &self.reload].iter() {
+            entities.push(bar.track);
+            entities.push(bar.fill);
+        }
+        entities
+    }
+}
+
+/// Hud holds a panel for every ship in the match, created by the HudSystem as ships appear.
+/// Panels fill the bottom corners first, left then right, then stack upward.
+pub struct Hud {
+    pub font: FontHandle,
+    pub panels: Vec<PlayerHud>,
+    // danger zone countdown, at the top of the window under the scores
+    pub zone_text: Entity,
+    // recent round results, newest first
+    pub kill_feed: Vec<Entity>,
+}
+
+impl Hud {
+    /// Every entity drawing the HUD, for clearing it away at the end of a match
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities = vec![self.zone_text];
+        entities.extend(self.kill_feed.iter().cloned());
+        for panel in self.panels.iter() {
+            entities.extend(panel.entities());
+        }
+        entities
+    }
+}
+
+/// Storages needed to draw a new panel in the HUD
+pub struct HudStorages<'a, 's> {
+    pub entities: &'a Entities<'s>,
+    pub ui_transforms: &'a mut WriteStorage<'s, UiTransform>,
+    pub ui_images: &'a mut WriteStorage<'s, UiImage>,
+    pub ui_texts: &'a mut WriteStorage<'s, UiText>,
+}
+
+pub fn initialize_hud(world: &mut World) {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+
+    let zone_text = world
+        .create_entity()
+        .with(UiTransform::new(
+            "ZoneTimer".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+            0.0, -110.0, 1.0, 500.0, 30.0,
+        ))
+        .with(UiText::new(
+            font.clone(),
+            "".to_string(),
+            [1.0, 0.4, 0.3, 1.0],
+            25.0,
+        )).build();
+
+    let kill_feed = (0..KILL_FEED_LENGTH)
+        .map(|line| {
+            world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("KillFeed{}", line), Anchor::TopLeft, Anchor::TopLeft,
+                    HUD_MARGIN / 2.0, -HUD_MARGIN / 2.0 - line as f32 * KILL_FEED_LINE, 1.0, 500.0, KILL_FEED_LINE,
+                ))
+                .with(UiText::new(
+                    font.clone(),
+                    "".to_string(),
+                    [1.0, 1.0, 1.0, 1.0],
+                    18.0,
+                )).build()
+        })
+        .collect();
+
+    world.insert(Hud { font, panels: Vec::new(), zone_text, kill_feed });
+}
+
+/// Draws the panel for `ship` in the `slot`th place
+pub fn create_player_hud(storages: &mut HudStorages, font: &FontHandle, slot: usize, ship: Entity, side: Side) -> PlayerHud {
+    let row = (slot / 2) as f32;
+
+    // anchored to the outside of the window, so shrinking a fill pulls it toward the edge
+    let (anchor, x, score_x) = if slot % 2 == 0 {
+        (Anchor::BottomLeft, HUD_MARGIN, -SCORE_SPACING * (row + 0.5))
+    } else {
+        (Anchor::BottomRight, -HUD_MARGIN, SCORE_SPACING * (row + 0.5))
+    };
+    let base = row * PANEL_HEIGHT;
+
+    let special_text = create_text(
+        storages, font, format!("Special{}", slot), anchor, x, base + SPECIAL_TEXT_Y, 300.0, 30.0, 25.0,
+    );
+    let score_text = create_text(
+        storages, font, format!("Score{}", slot), Anchor::TopMiddle, score_x, -50.0, 200.0, 50.0, 50.0,
+    );
+
+    let mut bar = |kind: HudBarKind| create_bar(storages, slot, side, kind, anchor.clone(), x, base);
+
+    PlayerHud {
+        ship,
+        side,
+        structure: bar(HudBarKind::Structure),
+        shield: bar(HudBarKind::Shield),
+        energy: bar(HudBarKind::Energy),
+        reload: bar(HudBarKind::Reload),
+        special_text,
+        score_text,
+    }
+}
+
+fn create_bar(storages: &mut HudStorages, slot: usize, side: Side, kind: HudBarKind, anchor: Anchor, x: f32, base: f32) -> HudBar {
+    let (height, y) = kind.layout();
+    let [r, g, b, a] = kind.colour(side);
+
+    let track = storages.entities.create();
+    storages.ui_transforms.insert(track, UiTransform::new(
+        format!("{:?}Track{}", kind, slot), anchor.clone(), anchor.clone(),
+        x, base + y, 1.0, HUD_BAR_WIDTH, height,
+    )).expect("Unable to place hud bar");
+    storages.ui_images.insert(track, UiImage::SolidColor([r * TRACK_SHADE, g * TRACK_SHADE, b * TRACK_SHADE, a * 0.6]))
+        .expect("Unable to colour hud bar");
+
+    let fill = storages.entities.create();
+    storages.ui_transforms.insert(fill, UiTransform::new(
+        format!("{:?}{}", kind, slot), anchor.clone(), anchor,
+        x, base + y, 2.0, HUD_BAR_WIDTH, height,
+    )).expect("Unable to place hud bar");
+    storages.ui_images.insert(fill, UiImage::SolidColor([r, g, b, a]))
+        .expect("Unable to colour hud bar");
+
+    HudBar { track, fill, shown: 1.0 }
+}
+
+fn create_text(
+    storages: &mut HudStorages,
+    font: &FontHandle,
+    id: String,
+    anchor: Anchor,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    font_size: f32,
+) -> Entity {
+    let text = storages.entities.create();
+    storages.ui_transforms.insert(text, UiTransform::new(id, anchor.clone(), anchor, x, y, 1.0, width, height))
+        .expect("Unable to place hud text");
+    storages.ui_texts.insert(text, UiText::new(font.clone(), "".to_string(), [1.0, 1.0, 1.0, 1.0], font_size))
+        .expect("Unable to write hud text");
+    text
+}
@@ -0,0 +1,72 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+use std::f32::consts::PI;
+
+use super::particle::{ParticleEmitter, ParticleStyle, HULL_SPARKS};
+
+/// Fractions of max structure below which a ship shows each tier of damage.
+/// Tier n is sprite n in the ship spritesheet, tier 0 being the pristine hull.
+pub const DAMAGE_TIERS: [f32; 3] = [0.66, 0.33, 0.10];
+
+/// Seconds a ship flashes red after losing structure
+pub const HIT_FLASH_TIME: f32 = 0.12;
+
+/// Smoke trailing from a damaged hull
+pub const SMOKE: ParticleStyle = ParticleStyle {
+    lifetime: 1.2,
+    speed: 0.4,
+    speed_spread: 0.3,
+    angle_spread: PI,
+    start_colour: (0.45, 0.42, 0.4, 0.6),
+    end_colour: (0.2, 0.2, 0.2, 0.0),
+    start_size: 6.0,
+    end_size: 16.0,
+};
+
+// smoke and spark intensity at each tier
+const SMOKE_INTENSITY: [f32; 4] = [0.0, 0.8, 2.0, 4.0];
+const SPARK_INTENSITY: [f32; 4] = [0.0, 0.0, 0.75, 2.0];
+
+/// HullDamage tracks how battered a ship looks, and the smoke and sparks pouring off it
+#[derive(Debug, Clone, Copy)]
+pub struct HullDamage {
+    pub tier: usize,
+    // structure last frame, so any loss can trigger a hit flash
+    pub last_structure: i32,
+    pub flash_timer: f32,
+    pub smoke: ParticleEmitter,
+    pub sparks: ParticleEmitter,
+}
+
+impl Component for HullDamage {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl HullDamage {
+    pub fn new(structure: i32) -> HullDamage {
+        HullDamage {
+            tier: 0,
+            last_structure: structure,
+            flash_timer: 0.0,
+            smoke: ParticleEmitter::new(SMOKE, 10.0, (0.0, 0.0), 0.0, 0.3),
+            sparks: ParticleEmitter::new(HULL_SPARKS, 4.0, (0.0, 0.0), 0.0, 0.5),
+        }
+    }
+
+    /// Moves to the tier for `structure`, turning the smoke and sparks up or down to match
+    pub fn set_tier(&mut self, structure: i32, max_structure: i32) {
+        self.tier = damage_tier(structure, max_structure);
+        self.smoke.intensity = SMOKE_INTENSITY[self.tier];
+        self.sparks.intensity = SPARK_INTENSITY[self.tier];
+    }
+}
+
+/// How many damage tiers a ship on `structure` out of `max_structure` has fallen through
+pub fn damage_tier(structure: i32, max_structure: i32) -> usize {
+    if max_structure <= 0 {
+        return 0;
+    }
+
+    let fraction = structure as f32 / max_structure as f32;
+    DAMAGE_TIERS.iter().filter(|threshold| fraction <= **threshold).count()
+}
@@ -0,0 +1,6 @@
+use amethyst::ecs::{Component, NullStorage};
+
+/// Marks an arrow drawn at the edge of the view, pointing at something off screen
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct Indicator;
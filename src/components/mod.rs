@@ -6,12 +6,43 @@ mod combat;
 mod ui;
 mod enemy;
 mod thrust;
+mod pilot;
+mod energy;
+mod special;
+mod shield;
+mod planet;
+mod asteroid;
+mod arena;
+mod ghost;
+mod starfield;
+mod particle;
+mod hull_damage;
+mod hud;
+mod indicator;
+mod radar;
+mod floating_text;
 
 pub use self::laser::{LaserImpact, Laser};
 pub use self::ship::{Ship, Side, initialise_ships};
 pub use self::animation::{AnimationPrefabData, Animation, AnimationId};
 pub use self::physical::{Physical};
-pub use self::combat::{Combat, LaserType};
-pub use self::ui::{ScoreBoard, ScoreText, StructureText, initialize_scoreboard, initialize_ship_hp_ui};
+pub use self::combat::{Combat, LaserType, DamageType, DamageReport};
+pub use self::ui::ScoreBoard;
 pub use self::enemy::EnemyAi;
-pub use self::thrust::{Thrust};
\ No newline at end of file
+pub use self::thrust::{Thrust};
+pub use self::pilot::{Pilot, ShipControl};
+pub use self::energy::Energy;
+pub use self::special::{Special, SpecialType};
+pub use self::shield::{Shield, ShieldShimmer, SHIMMER_TIME};
+pub use self::planet::{GravityWell, PlanetDefinition, ObstacleDefinition, initialise_planets, initialise_obstacles};
+pub use self::asteroid::{Asteroid, AsteroidField, MIN_SPLIT_RADIUS, initialise_asteroids, random_position, random_spin, spawn_asteroid};
+pub use self::arena::{ArenaBackground, DangerZone, DangerZoneBorder, Edge, initialise_arena, initialise_arena_bodies};
+pub use self::ghost::Ghost;
+pub use self::starfield::{Parallax, StarfieldDefinition, StarLayerDefinition, NebulaDefinition, initialise_starfield};
+pub use self::particle::{Particle, ParticleEmitter, ParticleStyle, EXHAUST, LIGHT_LASER_TRAIL, DARK_LASER_TRAIL,
+    HULL_SPARKS, WRECKAGE, spawn_particle, spawn_burst};
+pub use self::hull_damage::{HullDamage, DAMAGE_TIERS, HIT_FLASH_TIME, damage_tier};
+pub use self::hud::{Hud, HudBar, HudBarKind, HudStorages, PlayerHud, HUD_BAR_WIDTH, initialize_hud, create_player_hud};
+pub use self::indicator::Indicator;
+pub use self::radar::{Radar, RADAR_WIDTH, RADAR_MARGIN, initialize_radar};
+pub use self::floating_text::FloatingText;
\ No newline at end of file
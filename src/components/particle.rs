@@ -0,0 +1,268 @@
+use amethyst::{
+    assets::Handle,
+    core::{transform::Transform, math},
+    ecs::prelude::{Component, DenseVecStorage, Entities, LazyUpdate},
+    renderer::{SpriteRender, SpriteSheet, Transparent, resources::Tint, palette::Srgba},
+};
+
+use std::f32::consts::PI;
+
+use crate::states::RandomGen;
+
+// particles share the 16 pixel star.png sprite
+const PARTICLE_SPRITE_SIZE: f32 = 16.0;
+// particles are drawn just behind the ships
+const PARTICLE_Z: f32 = -0.1;
+
+/// ParticleStyle sets how particles move and fade over their life.
+/// Speeds are in pixels per frame like Physical, sizes in pixels and colours as rgba.
+#[derive(Debug, Clone, Copy)]
+pub struct ParticleStyle {
+    pub lifetime: f32,
+    pub speed: f32,
+    pub speed_spread: f32,
+    // particles leave within this many radians either side of their heading
+    pub angle_spread: f32,
+    pub start_colour: (f32, f32, f32, f32),
+    pub end_colour: (f32, f32, f32, f32),
+    pub start_size: f32,
+    pub end_size: f32,
+}
+
+/// Engine exhaust, blown out behind a thrusting ship
+pub const EXHAUST: ParticleStyle = ParticleStyle {
+    lifetime: 0.4,
+    speed: 3.0,
+    speed_spread: 1.0,
+    angle_spread: 0.25,
+    start_colour: (1.0, 0.85, 0.4, 0.9),
+    end_colour: (0.8, 0.2, 0.05, 0.0),
+    start_size: 7.0,
+    end_size: 2.0,
+};
+
+/// Glowing wake left by the light ship's lasers
+pub const LIGHT_LASER_TRAIL: ParticleStyle = ParticleStyle {
+    lifetime: 0.2,
+    speed: 0.2,
+    speed_spread: 0.2,
+    angle_spread: PI,
+    start_colour: (0.5, 1.0, 0.6, 0.6),
+    end_colour: (0.1, 0.6, 0.2, 0.0),
+    start_size: 4.0,
+    end_size: 1.0,
+};
+
+/// Glowing wake left by the dark ship's lasers
+pub const DARK_LASER_TRAIL: ParticleStyle = ParticleStyle {
+    start_colour: (1.0, 0.5, 0.4, 0.6),
+    end_colour: (0.6, 0.1, 0.1, 0.0),
+    ..LIGHT_LASER_TRAIL
+};
+
+/// Sparks thrown off a hull by a hit
+pub const HULL_SPARKS: ParticleStyle = ParticleStyle {
+    lifetime: 0.35,
+    speed: 4.0,
+    speed_spread: 2.5,
+    angle_spread: PI,
+    start_colour: (1.0, 1.0, 0.7, 1.0),
+    end_colour: (1.0, 0.4, 0.0, 0.0),
+    start_size: 3.0,
+    end_size: 1.0,
+};
+
+/// Burning wreckage left where a ship was destroyed
+pub const WRECKAGE: ParticleStyle = ParticleStyle {
+    lifetime: 1.5,
+    speed: 2.0,
+    speed_spread: 1.8,
+    angle_spread: PI,
+    start_colour: (1.0, 0.6, 0.2, 1.0),
+    end_colour: (0.25, 0.25, 0.25, 0.0),
+    start_size: 10.0,
+    end_size: 4.0,
+};
+
+/// A single particle, moved and faded by the ParticleSystem until its lifetime runs out
+#[derive(Debug, Clone, Copy)]
+pub struct Particle {
+    pub age: f32,
+    pub velocity: math::Vector2<f32>,
+    pub style: ParticleStyle,
+}
+
+impl Component for Particle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Particle {
+    /// How far through its life the particle is, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        (self.age / self.style.lifetime).min(1.0)
+    }
+
+    pub fn colour(&self) -> Srgba {
+        let t = self.progress();
+        let (start, end) = (self.style.start_colour, self.style.end_colour);
+        Srgba::new(
+            lerp(start.0, end.0, t),
+            lerp(start.1, end.1, t),
+            lerp(start.2, end.2, t),
+            lerp(start.3, end.3, t),
+        )
+    }
+
+    pub fn scale(&self) -> f32 {
+        lerp(self.style.start_size, self.style.end_size, self.progress()) / PARTICLE_SPRITE_SIZE
+    }
+}
+
+/// ParticleEmitter streams particles from its entity at `rate` per second, scaled by `intensity`.
+/// `offset` is in pixels from the entity and `heading` in radians from the way it faces,
+/// both turning with the entity. Particles keep `inherit` of a moving entity's velocity.
+#[derive(Debug, Clone, Copy)]
+pub struct ParticleEmitter {
+    pub style: ParticleStyle,
+    pub rate: f32,
+    pub intensity: f32,
+    pub offset: (f32, f32),
+    pub heading: f32,
+    pub inherit: f32,
+    // fractional particles carried over between frames
+    pub pending: f32,
+}
+
+impl Component for ParticleEmitter {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl ParticleEmitter {
+    pub fn new(style: ParticleStyle, rate: f32, offset: (f32, f32), heading: f32, inherit: f32) -> ParticleEmitter {
+        ParticleEmitter {
+            style,
+            rate,
+            intensity: 1.0,
+            offset,
+            heading,
+            inherit,
+            pending: 0.0,
+        }
+    }
+
+    /// Exhaust out of the back of a ship, off until the ship thrusts
+    pub fn exhaust() -> ParticleEmitter {
+        ParticleEmitter {
+            intensity: 0.0,
+            ..ParticleEmitter::new(EXHAUST, 90.0, (0.0, -36.0), PI, 0.5)
+        }
+    }
+
+    /// A wake that hangs in space behind a laser
+    pub fn laser_trail(style: ParticleStyle) -> ParticleEmitter {
+        ParticleEmitter::new(style, 60.0, (0.0, 0.0), PI, 0.0)
+    }
+
+    /// Queues however many particles are due after `delta` seconds from an entity at `transform`
+    /// moving at `velocity`
+    pub fn emit(
+        &mut self,
+        entities: &Entities,
+        lazy_update: &LazyUpdate,
+        sprite_sheet: &Handle<SpriteSheet>,
+        random_gen: &RandomGen,
+        transform: &Transform,
+        velocity: math::Vector2<f32>,
+        delta: f32,
+    ) {
+        self.pending += self.rate * self.intensity * delta;
+
+        if self.pending < 1.0 {
+            return;
+        }
+
+        let offset = transform.rotation() * math::Vector3::new(self.offset.0, self.offset.1, 0.0);
+        let x = transform.translation().x + offset.x;
+        let y = transform.translation().y + offset.y;
+
+        // heading of the entity, counterclockwise from up
+        let facing = transform.rotation() * math::Vector3::y();
+        let direction = (-facing.x).atan2(facing.y) + self.heading;
+
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            spawn_particle(
+                entities,
+                lazy_update,
+                sprite_sheet.clone(),
+                random_gen,
+                &self.style,
+                x,
+                y,
+                direction,
+                velocity * self.inherit,
+            );
+        }
+    }
+}
+
+/// Queues one particle at (x, y) heading in `direction` radians, counterclockwise from up.
+/// `inherited` is velocity carried over from whatever shed it.
+pub fn spawn_particle(
+    entities: &Entities,
+    lazy_update: &LazyUpdate,
+    sprite_sheet: Handle<SpriteSheet>,
+    random_gen: &RandomGen,
+    style: &ParticleStyle,
+    x: f32,
+    y: f32,
+    direction: f32,
+    inherited: math::Vector2<f32>,
+) {
+    let angle = direction + (random_gen.next_f32() * 2.0 - 1.0) * style.angle_spread;
+    let speed = (style.speed + (random_gen.next_f32() * 2.0 - 1.0) * style.speed_spread).max(0.0);
+
+    let particle = Particle {
+        age: 0.0,
+        velocity: inherited + math::Vector2::new(-angle.sin(), angle.cos()) * speed,
+        style: *style,
+    };
+
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(x, y, PARTICLE_Z);
+    let scale = particle.scale();
+    transform.set_scale(math::Vector3::new(scale, scale, 1.0));
+
+    lazy_update
+        .create_entity(entities)
+        .with(Tint(particle.colour()))
+        .with(particle)
+        .with(transform)
+        .with(SpriteRender {
+            sprite_sheet,
+            sprite_number: 0,
+        })
+        .with(Transparent)
+        .build();
+}
+
+/// Throws `count` particles out from (x, y) in every direction the style allows
+pub fn spawn_burst(
+    entities: &Entities,
+    lazy_update: &LazyUpdate,
+    sprite_sheet: Handle<SpriteSheet>,
+    random_gen: &RandomGen,
+    style: &ParticleStyle,
+    count: usize,
+    x: f32,
+    y: f32,
+    inherited: math::Vector2<f32>,
+) {
+    for _ in 0..count {
+        spawn_particle(entities, lazy_update, sprite_sheet.clone(), random_gen, style, x, y, 0.0, inherited);
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
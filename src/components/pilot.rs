@@ -0,0 +1,35 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+/// Pilot decides who is flying a ship - a human at the keyboard or an AI script
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pilot {
+    Human,
+    Script(String),
+}
+
+impl Default for Pilot {
+    fn default() -> Self {
+        Pilot::Human
+    }
+}
+
+impl Component for Pilot {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// ShipControl holds the commands for a ship this frame.
+/// It is written by the InputSystem or the AiPilotSystem and read by movement and weapons.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ShipControl {
+    pub rotate: f32,
+    pub thrust: f32,
+    pub fire: bool,
+    // burn energy for extra acceleration
+    pub boost: bool,
+    // trigger the ship's special ability
+    pub special: bool,
+}
+
+impl Component for ShipControl {
+    type Storage = DenseVecStorage<Self>;
+}
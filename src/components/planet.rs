@@ -0,0 +1,99 @@
+use amethyst::{
+    core::{transform::Transform, math},
+    ecs::prelude::{Component, DenseVecStorage},
+    prelude::*,
+    renderer::Transparent,
+};
+
+use serde::{Serialize, Deserialize};
+
+use crate::resources::{SpriteSheetList, SPRITE_PLANET, SPRITE_ASTEROID};
+
+// planet.png is 256 pixels across
+const PLANET_SPRITE_SIZE: f32 = 256.0;
+// asteroid.png, reused for obstacles, is 128 pixels across
+const OBSTACLE_SPRITE_SIZE: f32 = 128.0;
+
+/// GravityWell pulls every Physical toward it and damages ships that crash into it.
+/// Obstacles are wells without mass, so they block without pulling.
+#[derive(Debug, Clone, Copy)]
+pub struct GravityWell {
+    pub mass: f32,
+    pub radius: f32,
+    pub damage: i32,
+}
+
+impl Component for GravityWell {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl GravityWell {
+    pub fn new(mass: f32, radius: f32, damage: i32) -> GravityWell {
+        GravityWell {
+            mass,
+            radius,
+            damage,
+        }
+    }
+}
+
+/// PlanetDefinition places a planet in the arena
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlanetDefinition {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub mass: f32,
+    pub damage: i32,
+}
+
+/// ObstacleDefinition places a fixed rock that blocks ships and lasers without any gravity
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ObstacleDefinition {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub damage: i32,
+}
+
+/// Creates a planet entity for each definition
+pub fn initialise_planets(world: &mut World, planets: &[PlanetDefinition]) {
+    let sprite_render = world.read_resource::<SpriteSheetList>().render(SPRITE_PLANET);
+
+    for planet in planets {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(planet.x, planet.y, -0.1);
+
+        let scale = planet.radius * 2.0 / PLANET_SPRITE_SIZE;
+        transform.set_scale(math::Vector3::new(scale, scale, scale));
+
+        world
+            .create_entity()
+            .with(GravityWell::new(planet.mass, planet.radius, planet.damage))
+            .maybe_with(sprite_render.clone())
+            .with(transform)
+            .with(Transparent)
+            .build();
+    }
+}
+
+/// Creates a massless gravity well for each obstacle, drawn as a large rock
+pub fn initialise_obstacles(world: &mut World, obstacles: &[ObstacleDefinition]) {
+    let sprite_render = world.read_resource::<SpriteSheetList>().render(SPRITE_ASTEROID);
+
+    for obstacle in obstacles {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(obstacle.x, obstacle.y, -0.1);
+
+        let scale = obstacle.radius * 2.0 / OBSTACLE_SPRITE_SIZE;
+        transform.set_scale(math::Vector3::new(scale, scale, scale));
+
+        world
+            .create_entity()
+            .with(GravityWell::new(0.0, obstacle.radius, obstacle.damage))
+            .maybe_with(sprite_render.clone())
+            .with(transform)
+            .with(Transparent)
+            .build();
+    }
+}
@@ -0,0 +1,71 @@
+use amethyst::{
+    core::Hidden,
+    ecs::prelude::Entity,
+    prelude::*,
+    ui::{Anchor, UiImage, UiTransform},
+};
+
+use crate::resources::ArenaDefinition;
+
+/// Width of the radar in the HUD, its height follows the shape of the arena
+pub const RADAR_WIDTH: f32 = 180.0;
+/// Gap between the radar and the top right corner of the window
+pub const RADAR_MARGIN: f32 = 20.0;
+
+/// Radar is the toggleable mini map of the whole arena in the top right of the HUD.
+/// Blips are pooled: the RadarSystem adds more as they are needed and hides the spares.
+pub struct Radar {
+    pub visible: bool,
+    pub width: f32,
+    pub height: f32,
+    pub frame: Entity,
+    // outline of what the camera can see
+    pub view: Entity,
+    pub blips: Vec<Entity>,
+}
+
+impl Radar {
+    /// Every entity drawing the radar, for clearing it away at the end of a match
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities = vec![self.frame, self.view];
+        entities.extend(self.blips.iter().cloned());
+        entities
+    }
+}
+
+/// Creates the radar for the current arena, so it must come after `initialise_arena`.
+/// A radar left switched off in the last match stays off.
+pub fn initialize_radar(world: &mut World) {
+    let (width, height) = {
+        let arena = world.read_resource::<ArenaDefinition>();
+        (RADAR_WIDTH, RADAR_WIDTH * arena.height / arena.width)
+    };
+
+    let visible = world.try_fetch::<Radar>().map_or(true, |radar| radar.visible);
+
+    let frame = world
+        .create_entity()
+        .with(UiTransform::new(
+            "Radar".to_string(), Anchor::TopRight, Anchor::TopRight,
+            -RADAR_MARGIN, -RADAR_MARGIN, 1.0, width, height,
+        ))
+        .with(UiImage::SolidColor([0.05, 0.1, 0.15, 0.6]))
+        .build();
+
+    let view = world
+        .create_entity()
+        .with(UiTransform::new(
+            "RadarView".to_string(), Anchor::TopRight, Anchor::Middle,
+            0.0, 0.0, 2.0, 0.0, 0.0,
+        ))
+        .with(UiImage::SolidColor([1.0, 1.0, 1.0, 0.08]))
+        .build();
+
+    if !visible {
+        let mut hiddens = world.write_storage::<Hidden>();
+        hiddens.insert(frame, Hidden).expect("Unable to hide radar");
+        hiddens.insert(view, Hidden).expect("Unable to hide radar");
+    }
+
+    world.insert(Radar { visible, width, height, frame, view, blips: Vec::new() });
+}
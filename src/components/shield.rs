@@ -0,0 +1,103 @@
+use amethyst::ecs::{Component, DenseVecStorage, NullStorage};
+
+use super::combat::DamageType;
+
+/// Shield is a regenerating layer that soaks up damage before armour and structure
+#[derive(Debug, Clone, Copy)]
+pub struct Shield {
+    pub strength: f32,
+    pub max_strength: f32,
+    // strength per second once regeneration starts
+    pub regen_rate: f32,
+    // seconds without damage before regeneration starts
+    pub regen_delay: f32,
+    pub regen_timer: f32,
+
+    // fraction of incoming damage of each type the shield can absorb
+    pub laser_effectiveness: f32,
+    pub collision_effectiveness: f32,
+
+    // seconds left on the shimmer effect after a hit
+    pub shimmer_timer: f32,
+}
+
+impl Component for Shield {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Marks the child entity that draws a ship's shield shimmer
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct ShieldShimmer;
+
+pub const SHIMMER_TIME: f32 = 0.3;
+
+impl Shield {
+    pub fn new(
+        max_strength: f32,
+        regen_rate: f32,
+        regen_delay: f32,
+        laser_effectiveness: f32,
+        collision_effectiveness: f32,
+    ) -> Shield {
+        Shield {
+            strength: max_strength,
+            max_strength,
+            regen_rate,
+            regen_delay,
+            regen_timer: 0.0,
+            laser_effectiveness,
+            collision_effectiveness,
+            shimmer_timer: 0.0,
+        }
+    }
+
+    pub fn effectiveness(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Laser => self.laser_effectiveness,
+            DamageType::Collision => self.collision_effectiveness,
+        }
+    }
+
+    /// Soaks up as much of `damage` as the shield can, returning the amount absorbed
+    pub fn absorb(&mut self, damage: i32, damage_type: DamageType) -> i32 {
+        // any hit restarts the regeneration delay
+        self.regen_timer = self.regen_delay;
+
+        // whole points only, so what the shield loses is what the damage report shows
+        let absorbable = (damage as f32 * self.effectiveness(damage_type)).round();
+        let absorbed = absorbable.min(self.strength.floor()).max(0.0) as i32;
+
+        self.strength -= absorbed as f32;
+
+        if absorbed > 0 {
+            self.shimmer_timer = SHIMMER_TIME;
+        }
+
+        absorbed
+    }
+
+    pub fn regenerate(&mut self, delta_seconds: f32) {
+        self.shimmer_timer = (self.shimmer_timer - delta_seconds).max(0.0);
+
+        if self.regen_timer > 0.0 {
+            self.regen_timer -= delta_seconds;
+        } else {
+            self.strength = (self.strength + self.regen_rate * delta_seconds).min(self.max_strength);
+        }
+    }
+
+    pub fn refill(&mut self) {
+        self.strength = self.max_strength;
+        self.regen_timer = 0.0;
+        self.shimmer_timer = 0.0;
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max_strength > 0.0 {
+            self.strength / self.max_strength
+        } else {
+            0.0
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use amethyst::{
     core::{transform::Transform, Parent, Hidden, math},
-    ecs::prelude::{Component, DenseVecStorage, LazyUpdate},
+    ecs::prelude::{Component, DenseVecStorage, Entity, LazyUpdate},
     prelude::*,
     renderer::{SpriteRender, Transparent,
         resources::Tint,
@@ -8,17 +8,17 @@ use amethyst::{
     },
 };
 
-use super::physical::Physical;
-use super::combat::{Combat, LaserType};
 use super::enemy::EnemyAi;
 use super::thrust::Thrust;
-use crate::resources::{SpriteSheetList, AssetType};
-
-use crate::states::{ARENA_HEIGHT, ARENA_WIDTH};
+use super::pilot::{Pilot, ShipControl};
+use super::shield::ShieldShimmer;
+use super::particle::ParticleEmitter;
+use super::hull_damage::HullDamage;
+use crate::resources::{SpriteSheetList, SPRITE_THRUST, ShipDefinitions, ArenaDefinition, LIGHT_SHIP, DARK_SHIP};
 
 pub const SHIP_SCALING: f32 = 0.20;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub enum Side {
     Light,
     Dark,
@@ -27,13 +27,18 @@ pub enum Side {
 #[derive(Debug)]
 pub struct Ship {
     pub side: Side,
+    pub name: String,
+    // asset manifest id of the sprite its lasers are drawn with
+    pub laser_sprite_sheet: String,
     pub thrust_timer: f32,
 }
 
 impl Ship {
-    pub fn new(side: Side) -> Ship {
+    pub fn new(side: Side, name: String, laser_sprite_sheet: String) -> Ship {
         Ship {
             side,
+            name,
+            laser_sprite_sheet,
             thrust_timer: 0.0,
         }
     }
@@ -43,17 +48,15 @@ impl Component for Ship {
     type Storage = DenseVecStorage<Self>;
 }
 
-/// Initialises one ship on the light, and one ship on the dark.
-pub fn initialise_ships(world: &mut World) {
+/// Initialises one ship on the light, and one ship on the dark, flown by the given pilots,
+/// at the spawn points of the current arena.
+pub fn initialise_ships(world: &mut World, light_pilot: Pilot, dark_pilot: Pilot) {
     
     // Get SpriteSheetList
     let sprite_sheet_list = {
         world.try_fetch::<SpriteSheetList>().expect("Unable to fetch SpriteSheetList")
     };
 
-    let light_sprite_sheet_handle = sprite_sheet_list.get(AssetType::LightShip).unwrap();
-    let dark_sprite_sheet_handle = sprite_sheet_list.get(AssetType::DarkShip).unwrap();
-
     let mut light_transform = Transform::default();
     let mut dark_transform = Transform::default();
 
@@ -61,51 +64,59 @@ pub fn initialise_ships(world: &mut World) {
     light_transform.set_scale(math::Vector3::new(SHIP_SCALING, SHIP_SCALING, SHIP_SCALING));
     dark_transform.set_scale(math::Vector3::new(SHIP_SCALING, SHIP_SCALING, SHIP_SCALING));
 
-    // rotate ships
-    light_transform.rotate_2d(1.60);
-    dark_transform.rotate_2d(-1.60);
-
-    let light_phys = Physical::new(43.0, 100.0, 1.25, 0.05);
-    let dark_phys = Physical::new(41.0, 100.0, 1.35, 0.05);
+    // Get ship stats from assets/ships, flying a stand-in for any that failed to load
+    let (light_definition, dark_definition) = {
+        let ship_definitions = world.read_resource::<ShipDefinitions>();
+        (ship_definitions.get_or_stand_in(LIGHT_SHIP), ship_definitions.get_or_stand_in(DARK_SHIP))
+    };
 
+    let light_phys = light_definition.physical();
+    let dark_phys = dark_definition.physical();
 
-    // Correctly position the ships.
-    let y = ARENA_HEIGHT / 2.0;
-    light_transform.set_translation_xyz(&light_phys.radius * 4.0, y, 0.0);
-    dark_transform.set_translation_xyz(ARENA_WIDTH - &dark_phys.radius * 4.0, y, 0.0);
 
-    // Assign the sprites for the light ship
-    let light_sprite_render = SpriteRender {
-        sprite_sheet: light_sprite_sheet_handle.clone(),
-        sprite_number: 0, // ship is the first sprite in the sprite_sheet
+    // Position and rotate the ships at the arena spawn points.
+    let (light_spawn, dark_spawn) = {
+        let arena = world.read_resource::<ArenaDefinition>();
+        (arena.light_spawn, arena.dark_spawn)
     };
+    light_transform.set_translation_xyz(light_spawn.x, light_spawn.y, 0.0);
+    light_transform.rotate_2d(light_spawn.rotation);
+    dark_transform.set_translation_xyz(dark_spawn.x, dark_spawn.y, 0.0);
+    dark_transform.rotate_2d(dark_spawn.rotation);
 
-    // Assign the sprites for the dark ship
-    let dark_sprite_render = SpriteRender {
-        sprite_sheet: dark_sprite_sheet_handle.clone(),
-        sprite_number: 0, // ship is the first sprite in the sprite_sheet
-    };
+    // Assign the sprites for each ship, the pristine hull being the first in the sprite_sheet.
+    // A ship whose sprites could not be loaded is drawn with the placeholder.
+    let light_sprite_render = sprite_sheet_list.render(&light_definition.sprite_sheet);
+    let dark_sprite_render = sprite_sheet_list.render(&dark_definition.sprite_sheet);
 
     // Load resources for adding thrust
     let lazy = world.try_fetch::<LazyUpdate>().expect("Unable to load LazyUpdate");
 
 
-    // Get Sprite sheet handle
-    let thrust_sprite_sheet_handle = sprite_sheet_list.get(AssetType::Thrust).unwrap();
-
     // Construct sprite render for thruster
-    let thrust_sprite_render = SpriteRender {
-        sprite_sheet: thrust_sprite_sheet_handle.clone(),
-        sprite_number: 0,
-    };
+    let thrust_sprite_render = sprite_sheet_list.render(SPRITE_THRUST);
 
     // Create a light ship entity.
     let light_ship = world.entities().create();
-    lazy.insert(light_ship, Ship::new(Side::Light));
-    lazy.insert(light_ship, light_sprite_render.clone());
+    lazy.insert(light_ship, Ship::new(Side::Light, light_definition.name.clone(), light_definition.laser_sprite_sheet.clone()));
+    if let Some(sprite_render) = light_sprite_render.clone() {
+        lazy.insert(light_ship, sprite_render);
+    }
     lazy.insert(light_ship, light_transform);
     lazy.insert(light_ship, light_phys.clone());
-    lazy.insert(light_ship, Combat::new(150, 6, 20, 6.0, 10.0, LaserType::Single, 0.2, 25, 30.0, 6.0, 5.0));
+    lazy.insert(light_ship, light_definition.combat());
+    lazy.insert(light_ship, HullDamage::new(light_definition.structure));
+    lazy.insert(light_ship, light_definition.energy());
+    if let Some(special) = light_definition.special() {
+        lazy.insert(light_ship, special);
+    }
+    if let Some(shield) = light_definition.shield() {
+        lazy.insert(light_ship, shield);
+        create_shield_shimmer(world, &lazy, light_ship, light_sprite_render.clone());
+    }
+    lazy.insert(light_ship, ParticleEmitter::exhaust());
+    lazy.insert(light_ship, light_pilot);
+    lazy.insert(light_ship, ShipControl::default());
 
     // Create thrust entity for light ship
     let light_thrust = world.entities().create();
@@ -115,10 +126,9 @@ pub fn initialise_ships(world: &mut World) {
         Thrust {
         show: false,
     });
-    lazy.insert(
-        light_thrust,
-        thrust_sprite_render.clone(),
-    );
+    if let Some(sprite_render) = thrust_sprite_render.clone() {
+        lazy.insert(light_thrust, sprite_render);
+    }
     lazy.insert(light_thrust, Parent::new(light_ship));
 
     let mut light_thrust_transform = Transform::from(math::Vector3::<f32>::new(
@@ -133,11 +143,25 @@ pub fn initialise_ships(world: &mut World) {
     
     // Create a dark ship entity.
     let dark_ship = world.entities().create();
-    lazy.insert(dark_ship, Ship::new(Side::Dark));
-    lazy.insert(dark_ship, dark_sprite_render.clone());
+    lazy.insert(dark_ship, Ship::new(Side::Dark, dark_definition.name.clone(), dark_definition.laser_sprite_sheet.clone()));
+    if let Some(sprite_render) = dark_sprite_render.clone() {
+        lazy.insert(dark_ship, sprite_render);
+    }
     lazy.insert(dark_ship, dark_transform);
     lazy.insert(dark_ship, dark_phys.clone());
-    lazy.insert(dark_ship, Combat::new(130, 5, 15, 4.0, 10.0, LaserType::Dual, 0.3, 25, 30.0, 6.0, 5.0));
+    lazy.insert(dark_ship, dark_definition.combat());
+    lazy.insert(dark_ship, HullDamage::new(dark_definition.structure));
+    lazy.insert(dark_ship, dark_definition.energy());
+    if let Some(special) = dark_definition.special() {
+        lazy.insert(dark_ship, special);
+    }
+    if let Some(shield) = dark_definition.shield() {
+        lazy.insert(dark_ship, shield);
+        create_shield_shimmer(world, &lazy, dark_ship, dark_sprite_render.clone());
+    }
+    lazy.insert(dark_ship, ParticleEmitter::exhaust());
+    lazy.insert(dark_ship, dark_pilot);
+    lazy.insert(dark_ship, ShipControl::default());
 
     // Create thrust entity for dark ship
     let dark_thrust = world.entities().create();
@@ -150,10 +174,9 @@ pub fn initialise_ships(world: &mut World) {
         Thrust {
         show: false,
     });
-    lazy.insert(
-        dark_thrust,
-        thrust_sprite_render.clone(),
-    );
+    if let Some(sprite_render) = thrust_sprite_render.clone() {
+        lazy.insert(dark_thrust, sprite_render);
+    }
     lazy.insert(dark_thrust, Parent::new(dark_ship));
     let mut dark_thrust_transform = Transform::from(math::Vector3::<f32>::new(
         0., -240., 0.
@@ -164,4 +187,22 @@ pub fn initialise_ships(world: &mut World) {
     lazy.insert(dark_thrust, Transparent);
     lazy.insert(dark_thrust, Hidden);
     lazy.insert(dark_thrust, tint);
-}
\ No newline at end of file
+}
+
+/// Creates the hidden child entity that shimmers around a ship when its shield is hit.
+/// It reuses the ship sprite, slightly enlarged and tinted by the ShieldSystem.
+fn create_shield_shimmer(world: &World, lazy: &LazyUpdate, ship: Entity, sprite_render: Option<SpriteRender>) {
+    let shimmer = world.entities().create();
+
+    let mut shimmer_transform = Transform::from(math::Vector3::<f32>::new(0., 0., 0.1));
+    shimmer_transform.set_scale(math::Vector3::new(1.15, 1.15, 1.15));
+
+    lazy.insert(shimmer, ShieldShimmer);
+    if let Some(sprite_render) = sprite_render {
+        lazy.insert(shimmer, sprite_render);
+    }
+    lazy.insert(shimmer, Parent::new(ship));
+    lazy.insert(shimmer, shimmer_transform);
+    lazy.insert(shimmer, Transparent);
+    lazy.insert(shimmer, Hidden);
+}
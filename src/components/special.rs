@@ -0,0 +1,88 @@
+use amethyst::ecs::{DenseVecStorage, Component};
+
+use serde::{Serialize, Deserialize};
+
+/// The Star Control style special abilities a ship can carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SpecialType {
+    // short range teleport along the ship's heading
+    Blink,
+    // ship is hidden until the duration ends
+    Cloak,
+    // ship ignores damage until the duration ends
+    Shield,
+    // pulls the enemy ship toward this one for the duration
+    Tractor,
+    // instant burst of speed beyond the normal maximum
+    Afterburner,
+}
+
+impl SpecialType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpecialType::Blink => "BLINK",
+            SpecialType::Cloak => "CLOAK",
+            SpecialType::Shield => "SHIELD",
+            SpecialType::Tractor => "TRACTOR",
+            SpecialType::Afterburner => "BURNER",
+        }
+    }
+}
+
+/// Special tracks the cooldown and active time of a ship's special ability
+#[derive(Debug, Clone, Copy)]
+pub struct Special {
+    pub special_type: SpecialType,
+    pub cooldown: f32,
+    pub cooldown_timer: f32,
+    pub duration: f32,
+    pub active_timer: f32,
+}
+
+impl Component for Special {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Special {
+    pub fn new(special_type: SpecialType, cooldown: f32, duration: f32) -> Special {
+        Special {
+            special_type,
+            cooldown,
+            cooldown_timer: 0.0,
+            duration,
+            active_timer: 0.0,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown_timer <= 0.0
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active_timer > 0.0
+    }
+
+    pub fn is_shielding(&self) -> bool {
+        self.special_type == SpecialType::Shield && self.is_active()
+    }
+
+    pub fn activate(&mut self) {
+        self.cooldown_timer = self.cooldown;
+        self.active_timer = self.duration;
+    }
+
+    /// Counts down the timers, returning true on the frame the active effect ends
+    pub fn tick(&mut self, delta_seconds: f32) -> bool {
+        let was_active = self.is_active();
+
+        self.cooldown_timer = (self.cooldown_timer - delta_seconds).max(0.0);
+        self.active_timer = (self.active_timer - delta_seconds).max(0.0);
+
+        was_active && !self.is_active()
+    }
+
+    pub fn reset(&mut self) {
+        self.cooldown_timer = 0.0;
+        self.active_timer = 0.0;
+    }
+}
@@ -0,0 +1,159 @@
+use amethyst::{
+    core::{transform::Transform, math},
+    ecs::prelude::{Component, DenseVecStorage},
+    prelude::*,
+    renderer::{SpriteRender, Transparent, resources::Tint, palette::Srgba},
+};
+
+use serde::{Serialize, Deserialize};
+
+use crate::resources::{SpriteSheetList, SPRITE_STAR, SPRITE_NEBULA, ArenaDefinition};
+use crate::states::RandomGen;
+use crate::systems::camera::{max_zoom, VIEW_WIDTH, VIEW_HEIGHT};
+
+// star.png and nebula.png sizes in pixels
+const STAR_SPRITE_SIZE: f32 = 16.0;
+const NEBULA_SPRITE_SIZE: f32 = 256.0;
+// starfield layers sit between the arena backdrop at -0.9 and the action at 0
+const NEBULA_Z: f32 = -0.8;
+const STAR_Z: f32 = -0.7;
+
+/// Parallax draws a background sprite relative to the camera rather than the arena.
+/// `depth` is how much of the camera's movement shows on it: 1.0 is fixed in the arena,
+/// 0.0 is painted on the lens. Tiled sprites repeat every `tile` so they always fill the view,
+/// untiled ones are placed once at (x, y) in the arena.
+#[derive(Debug, Clone, Copy)]
+pub struct Parallax {
+    pub x: f32,
+    pub y: f32,
+    pub depth: f32,
+    pub scale: f32,
+    pub tile: Option<(f32, f32)>,
+}
+
+impl Component for Parallax {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// StarLayerDefinition scatters `count` stars of `size` pixels across the view at one depth
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StarLayerDefinition {
+    pub count: usize,
+    pub depth: f32,
+    pub size: f32,
+    pub brightness: f32,
+}
+
+/// NebulaDefinition places a tinted gas cloud `size` pixels across behind the stars
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NebulaDefinition {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    pub depth: f32,
+    pub colour: (f32, f32, f32, f32),
+}
+
+/// StarfieldDefinition is the procedural backdrop of an arena.
+/// Arenas without one get three layers of plain stars.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StarfieldDefinition {
+    #[serde(default)]
+    pub layers: Vec<StarLayerDefinition>,
+    #[serde(default)]
+    pub nebulae: Vec<NebulaDefinition>,
+}
+
+impl Default for StarfieldDefinition {
+    fn default() -> Self {
+        StarfieldDefinition {
+            layers: vec![
+                StarLayerDefinition { count: 160, depth: 0.1, size: 2.0, brightness: 0.4 },
+                StarLayerDefinition { count: 80, depth: 0.25, size: 3.0, brightness: 0.65 },
+                StarLayerDefinition { count: 30, depth: 0.5, size: 4.0, brightness: 0.9 },
+            ],
+            nebulae: Vec::new(),
+        }
+    }
+}
+
+/// Scatters the starfield of the current arena. Stars are spread over a tile as large as the
+/// widest view of the arena, which the StarfieldSystem repeats around the camera.
+pub fn initialise_starfield(world: &mut World, starfield: &StarfieldDefinition) {
+    let (star_sheet, nebula_sheet) = {
+        let sprite_sheet_list = world.read_resource::<SpriteSheetList>();
+        (
+            sprite_sheet_list.get(SPRITE_STAR).cloned(),
+            sprite_sheet_list.get(SPRITE_NEBULA).cloned(),
+        )
+    };
+
+    let tile = {
+        let arena = world.read_resource::<ArenaDefinition>();
+        let zoom = max_zoom(&arena.state());
+        (VIEW_WIDTH * zoom, VIEW_HEIGHT * zoom)
+    };
+
+    if let Some(sprite_sheet) = nebula_sheet {
+        for nebula in starfield.nebulae.iter() {
+            let (r, g, b, a) = nebula.colour;
+            let scale = nebula.size / NEBULA_SPRITE_SIZE;
+
+            create_parallax_sprite(
+                world,
+                SpriteRender { sprite_sheet: sprite_sheet.clone(), sprite_number: 0 },
+                Parallax { x: nebula.x, y: nebula.y, depth: nebula.depth, scale, tile: None },
+                NEBULA_Z,
+                Srgba::new(r, g, b, a),
+            );
+        }
+    }
+
+    if let Some(sprite_sheet) = star_sheet {
+        for (index, layer) in starfield.layers.iter().enumerate() {
+            for _ in 0..layer.count {
+                let (x, y, twinkle, warmth) = {
+                    let random_gen = world.read_resource::<RandomGen>();
+                    (
+                        random_gen.next_f32() * tile.0,
+                        random_gen.next_f32() * tile.1,
+                        0.7 + random_gen.next_f32() * 0.3,
+                        random_gen.next_f32() * 0.2 - 0.1,
+                    )
+                };
+
+                // a little warmer or cooler than white, so the field is not flat
+                let brightness = layer.brightness * twinkle;
+                let tint = Srgba::new(
+                    brightness * (1.0 + warmth),
+                    brightness,
+                    brightness * (1.0 - warmth),
+                    1.0,
+                );
+
+                create_parallax_sprite(
+                    world,
+                    SpriteRender { sprite_sheet: sprite_sheet.clone(), sprite_number: 0 },
+                    Parallax { x, y, depth: layer.depth, scale: layer.size / STAR_SPRITE_SIZE, tile: Some(tile) },
+                    STAR_Z + index as f32 * 0.01,
+                    tint,
+                );
+            }
+        }
+    }
+}
+
+fn create_parallax_sprite(world: &mut World, sprite_render: SpriteRender, parallax: Parallax, z: f32, tint: Srgba) {
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(parallax.x, parallax.y, z);
+    transform.set_scale(math::Vector3::new(parallax.scale, parallax.scale, 1.0));
+
+    world
+        .create_entity()
+        .with(parallax)
+        .with(sprite_render)
+        .with(transform)
+        .with(Tint(tint))
+        .with(Transparent)
+        .build();
+}
@@ -0,0 +1,126 @@
+//! Headless duels between two AI scripts, used to tune pilots without opening a window.
+//! Each round runs the same rule systems as a real match, on a World with nothing to draw
+//! and no sound, so batch results follow the game as it changes.
+
+use std::cmp::Ordering;
+
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Dispatcher, DispatcherBuilder, World, WorldExt},
+    renderer::{SpriteRender, Transparent},
+};
+
+use crate::audio::Sounds;
+use crate::components::{initialise_arena_bodies, initialise_ships, HullDamage, Pilot, ScoreBoard, Side};
+use crate::resources::{AiScripts, ArenaDefinition, CombatLog, PrefabList, ShipDefinitions, REQUIRED_SHIPS};
+use crate::states::{add_match_rules, RandomGen};
+
+// simulate at a fixed 60 fps
+const TIME_STEP: f32 = 1.0 / 60.0;
+// rounds that last longer than this are a draw
+const MAX_ROUND_TIME: f32 = 120.0;
+
+/// Tally of a batch of headless rounds
+#[derive(Debug, Default)]
+pub struct BatchResult {
+    pub light_wins: u32,
+    pub dark_wins: u32,
+    pub draws: u32,
+}
+
+/// Plays `rounds` rounds between two AI scripts in `arena` and reports who won
+pub fn run_batch(
+    scripts: AiScripts,
+    ship_definitions: ShipDefinitions,
+    arena: &ArenaDefinition,
+    light: &str,
+    dark: &str,
+    rounds: u32,
+) -> Result<BatchResult, String> {
+    for script in [light, dark].iter() {
+        if !scripts.contains(script) {
+            return Err(format!("No AI script named {}, available: {:?}", script, scripts.names()));
+        }
+    }
+    for name in REQUIRED_SHIPS.iter() {
+        if ship_definitions.get(name).is_none() {
+            return Err(format!("Missing ship definition ships/{}.ron", name));
+        }
+    }
+
+    let mut world = World::new();
+    let mut dispatcher = add_match_rules(DispatcherBuilder::new()).build();
+    dispatcher.setup(&mut world);
+
+    // components the rule systems never read, but that ships and asteroids are created with
+    world.register::<HullDamage>();
+    world.register::<SpriteRender>();
+    world.register::<Transparent>();
+
+    world.insert(scripts);
+    world.insert(ship_definitions);
+    world.insert(RandomGen);
+    world.insert(Sounds::default());
+    world.insert(PrefabList::default());
+    world.write_resource::<Time>().set_delta_seconds(TIME_STEP);
+
+    let mut result = BatchResult::default();
+
+    for round in 0..rounds {
+        match run_round(&mut world, &mut dispatcher, arena, light, dark) {
+            Some(Side::Light) => result.light_wins += 1,
+            Some(Side::Dark) => result.dark_wins += 1,
+            None => result.draws += 1,
+        }
+        log::info!("Round {} complete: {:?}", round + 1, result);
+    }
+
+    Ok(result)
+}
+
+/// Returns the winning side, or None for a draw
+fn run_round(
+    world: &mut World,
+    dispatcher: &mut Dispatcher<'static, 'static>,
+    arena: &ArenaDefinition,
+    light: &str,
+    dark: &str,
+) -> Option<Side> {
+    // every round starts from an empty arena
+    world.delete_all();
+    world.insert(ScoreBoard::default());
+    world.insert(CombatLog::default());
+
+    initialise_arena_bodies(world, arena.clone());
+    initialise_ships(world, Pilot::Script(light.to_string()), Pilot::Script(dark.to_string()));
+    world.maintain();
+
+    let mut elapsed = 0.0;
+
+    while elapsed < MAX_ROUND_TIME {
+        elapsed += TIME_STEP;
+
+        dispatcher.dispatch(world);
+        world.maintain();
+
+        // nothing turns hits into floating damage numbers here
+        world.write_resource::<CombatLog>().hits.clear();
+
+        // the WinnerSystem scores a destroyed ship for the other side
+        let (light_score, dark_score) = {
+            let scores = world.read_resource::<ScoreBoard>();
+            (scores.score(Side::Light), scores.score(Side::Dark))
+        };
+
+        if light_score + dark_score > 0 {
+            return match light_score.cmp(&dark_score) {
+                Ordering::Greater => Some(Side::Light),
+                Ordering::Less => Some(Side::Dark),
+                // both ships went down on the same frame
+                Ordering::Equal => None,
+            };
+        }
+    }
+
+    None
+}
\ No newline at end of file
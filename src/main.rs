@@ -5,11 +5,16 @@ mod audio;
 mod resources;
 mod components;
 mod states;
+mod headless;
+mod validate;
 
 extern crate specs_derive;
 
+use std::path::Path;
+
 use amethyst::{
     animation::AnimationBundle,
+    config::Config,
     assets::{HotReloadBundle, PrefabLoaderSystemDesc},
     core::{TransformBundle, SystemExt},
     prelude::*,
@@ -27,6 +32,8 @@ use amethyst::{
 
 use audio::Music;
 use crate::components::{AnimationPrefabData, AnimationId};
+use crate::resources::{AiScripts, AI_SCRIPT_DIR, ShipDefinitions, SHIP_DIR, Arenas, ARENA_DIR, DEFAULT_ARENA,
+    FeedbackSettings, FEEDBACK_CONFIG, AssetManifest, MANIFEST_FILE, ModPacks, ModSettings, MOD_DIR, MODS_CONFIG};
 use systems::*;
 
 const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
@@ -36,17 +43,45 @@ fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
 
     let app_root = application_root_dir()?;
+
+    // This line is not mentioned in the pong tutorial as it is specific to the context
+    // of the git repository. It only is a different location to load the assets from.
+    let assets_dir = app_root.join("assets");
+
+    let mut ai_scripts = AiScripts::load(&assets_dir.join(AI_SCRIPT_DIR));
+    let mut ship_definitions = ShipDefinitions::load(&assets_dir.join(SHIP_DIR));
+    let mut arenas = Arenas::load(&assets_dir.join(ARENA_DIR));
+    let mut asset_manifest = AssetManifest::load_or_empty(&assets_dir.join(MANIFEST_FILE));
+
+    // content packs in `mods` add to and override the base content, unless turned off in the mods screen
+    let mod_settings = ModSettings::load(app_root.join("config").join(MODS_CONFIG))
+        .unwrap_or_default();
+    let mut mod_packs = ModPacks::scan(&app_root.join(MOD_DIR), mod_settings);
+    mod_packs.apply(&mut asset_manifest, &mut ship_definitions, &mut arenas, &mut ai_scripts);
+
+    // `paladin batch <light script> <dark script> [rounds] [arena]` pits two AI scripts against
+    // each other without opening a window
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("batch") {
+        return run_batch(ai_scripts, ship_definitions, &arenas, &args[2..]);
+    }
+
+    // `paladin validate-assets` checks every asset the game refers to, also without a window
+    if args.get(1).map(String::as_str) == Some("validate-assets") {
+        return run_validate(&assets_dir, &asset_manifest, &ship_definitions, &arenas, &mod_packs);
+    }
+
     let display_config_path = app_root.join("config").join("display.ron");
 
     let binding_path = app_root.join("config").join("bindings.ron");
 
+    // screen shake and hit-stop can be turned off for comfort, and default to on
+    let feedback_settings = FeedbackSettings::load(app_root.join("config").join(FEEDBACK_CONFIG))
+        .unwrap_or_default();
+
     let input_bundle = InputBundle::<StringBindings>::new()
         .with_bindings_from_file(binding_path)?;
 
-    // This line is not mentioned in the pong tutorial as it is specific to the context
-    // of the git repository. It only is a different location to load the assets from.
-    let assets_dir = app_root.join("assets");
-    
     let game_data = GameDataBuilder::default()
         // Prefabbundlecar
         .with_system_desc(
@@ -90,11 +125,87 @@ fn main() -> amethyst::Result<()> {
     let mut game: Application<GameData> = 
         ApplicationBuilder::new(
             assets_dir,
-            crate::states::WelcomeScreen::default()
+            crate::states::LoadingState::new(Box::new(crate::states::WelcomeScreen::default()))
             )?
+            .with_resource(ai_scripts)
+            .with_resource(ship_definitions)
+            .with_resource(arenas)
+            .with_resource(asset_manifest)
+            .with_resource(mod_packs)
+            .with_resource(feedback_settings)
             .build(game_data)?;
 
     game.run();
 
     Ok(())
 }
+
+fn run_validate(
+    assets_dir: &Path,
+    asset_manifest: &AssetManifest,
+    ship_definitions: &ShipDefinitions,
+    arenas: &Arenas,
+    mod_packs: &ModPacks,
+) -> amethyst::Result<()> {
+    let problems = validate::validate_assets(assets_dir, asset_manifest, ship_definitions, arenas, mod_packs);
+
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+
+    if problems.is_empty() {
+        println!("All assets are valid");
+        Ok(())
+    } else {
+        Err(amethyst::Error::from_string(format!("Found {} asset problems", problems.len())))
+    }
+}
+
+fn run_batch(
+    ai_scripts: AiScripts,
+    ship_definitions: ShipDefinitions,
+    arenas: &Arenas,
+    args: &[String],
+) -> amethyst::Result<()> {
+    let (light, dark) = match (args.get(0), args.get(1)) {
+        (Some(light), Some(dark)) => (light, dark),
+        _ => {
+            eprintln!("Usage: paladin batch <light script> <dark script> [rounds] [arena]");
+            eprintln!("Available AI scripts: {:?}", ai_scripts.names());
+            eprintln!("Available arenas: {:?}", arenas.names());
+            return Err(amethyst::Error::from_string("Missing AI script names for batch"));
+        }
+    };
+
+    let rounds = match args.get(2).map(|rounds| rounds.parse::<u32>()) {
+        None => 10,
+        Some(Ok(rounds)) => rounds,
+        Some(Err(e)) => {
+            return Err(amethyst::Error::from_string(
+                format!("Invalid number of rounds {}: {}", args[2], e)
+            ));
+        }
+    };
+
+    let arena_name = args.get(3).map(String::as_str).unwrap_or(DEFAULT_ARENA);
+    let arena = match arenas.get(arena_name) {
+        Some(arena) => arena.clone(),
+        // fall back to empty space only if the default arena is missing
+        None if args.get(3).is_none() => Default::default(),
+        None => {
+            return Err(amethyst::Error::from_string(
+                format!("No arena named {}, available: {:?}", arena_name, arenas.names())
+            ));
+        }
+    };
+
+    let result = headless::run_batch(ai_scripts, ship_definitions, &arena, light, dark, rounds)
+        .map_err(amethyst::Error::from_string)?;
+
+    println!(
+        "{} (light) vs {} (dark) in {} over {} rounds: {} - {} with {} draws",
+        light, dark, arena.name, rounds, result.light_wins, result.dark_wins, result.draws
+    );
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use amethyst::config::Config;
+
+use serde::{Serialize, Deserialize};
+
+use crate::components::{AsteroidField, ObstacleDefinition, PlanetDefinition, Side, StarfieldDefinition};
+use crate::resources::ArenaState;
+
+/// Directory under `assets` that arena definitions are loaded from
+pub const ARENA_DIR: &str = "arenas";
+/// Arena selected first in the menu, and used by batch runs when none is named
+pub const DEFAULT_ARENA: &str = "standard";
+
+/// What happens to anything that reaches the edge of the arena
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum EdgeMode {
+    /// leave one side and come back on the other
+    Wrap,
+    /// solid walls that reflect anything hitting them, damaging ships by `damage`
+    Bounce {
+        damage: i32,
+    },
+    /// solid walls, plus a border that starts closing in after `delay` seconds at
+    /// `shrink_rate` pixels per second, up to `max_margin`, burning any ship caught outside it
+    DangerZone {
+        delay: f32,
+        shrink_rate: f32,
+        max_margin: f32,
+        damage_per_second: f32,
+    },
+}
+
+impl Default for EdgeMode {
+    fn default() -> Self {
+        EdgeMode::Wrap
+    }
+}
+
+/// SpawnPoint is where a ship starts each round.
+/// `rotation` turns the ship counterclockwise from facing up, in radians.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct SpawnPoint {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+}
+
+/// BackgroundDefinition is a sprite drawn behind everything else, centred on the arena.
+/// A `depth` below 1.0 makes it drift with the camera like the starfield.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BackgroundDefinition {
+    // asset manifest id
+    pub sprite_sheet: String,
+    #[serde(default = "default_one")]
+    pub scale: f32,
+    #[serde(default = "default_one")]
+    pub depth: f32,
+}
+
+fn default_one() -> f32 {
+    1.0
+}
+
+/// ArenaDefinition describes a playing field as loaded from `assets/arenas/*.ron`.
+/// The arena a match is played in is inserted into the world as a resource.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArenaDefinition {
+    pub name: String,
+
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub edge_mode: EdgeMode,
+
+    pub light_spawn: SpawnPoint,
+    pub dark_spawn: SpawnPoint,
+
+    // hazards
+    #[serde(default)]
+    pub planets: Vec<PlanetDefinition>,
+    #[serde(default)]
+    pub asteroids: Option<AsteroidField>,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDefinition>,
+
+    #[serde(default)]
+    pub starfield: StarfieldDefinition,
+    #[serde(default)]
+    pub background: Option<BackgroundDefinition>,
+    // asset manifest id of a track played on a loop during the match
+    #[serde(default)]
+    pub music: Option<String>,
+}
+
+/// An empty wrapping arena, used when no arena definitions could be loaded
+impl Default for ArenaDefinition {
+    fn default() -> Self {
+        ArenaDefinition {
+            name: "Open Space".to_string(),
+            width: 1600.0,
+            height: 1024.0,
+            edge_mode: EdgeMode::Wrap,
+            light_spawn: SpawnPoint { x: 172.0, y: 512.0, rotation: 1.6 },
+            dark_spawn: SpawnPoint { x: 1428.0, y: 512.0, rotation: -1.6 },
+            planets: Vec::new(),
+            asteroids: None,
+            obstacles: Vec::new(),
+            starfield: StarfieldDefinition::default(),
+            background: None,
+            music: None,
+        }
+    }
+}
+
+impl ArenaDefinition {
+    pub fn spawn(&self, side: Side) -> SpawnPoint {
+        match side {
+            Side::Light => self.light_spawn,
+            Side::Dark => self.dark_spawn,
+        }
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.edge_mode == EdgeMode::Wrap
+    }
+
+    /// The arena as seen by AI scripts and distance checks
+    pub fn state(&self) -> ArenaState {
+        ArenaState {
+            width: self.width,
+            height: self.height,
+            wraps: self.wraps(),
+            margin: 0.0,
+        }
+    }
+}
+
+/// Arenas holds every loaded arena definition keyed by file stem
+#[derive(Default)]
+pub struct Arenas {
+    arenas: BTreeMap<String, ArenaDefinition>,
+}
+
+impl Arenas {
+    /// Loads every `.ron` file in `dir`. Definitions that fail to parse are logged and skipped.
+    pub fn load(dir: &Path) -> Arenas {
+        let mut arenas = Arenas::default();
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Unable to read arena directory {:?}: {}", dir, e);
+                return arenas;
+            }
+        };
+
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+                continue;
+            }
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            match ArenaDefinition::load(&path) {
+                Ok(definition) => {
+                    arenas.arenas.insert(name, definition);
+                }
+                Err(e) => log::warn!("Unable to load arena definition {:?}: {}", path, e),
+            }
+        }
+
+        arenas
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ArenaDefinition> {
+        self.arenas.get(name)
+    }
+
+    /// Names of every loaded arena, in alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.arenas.keys().cloned().collect()
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &ArenaDefinition> {
+        self.arenas.values()
+    }
+
+    /// Adds every definition in `other`, replacing any with the same name
+    pub fn extend(&mut self, other: Arenas) {
+        self.arenas.extend(other.arenas);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    assets::{AssetStorage, Handle, Loader, Prefab, ProgressCounter},
+    audio::{OggFormat, Source, SourceHandle},
+    prelude::*,
+    renderer::sprite::SpriteSheetHandle,
+};
+
+use crate::components::AnimationPrefabData;
+use super::assets::{get_animation_prefab_handle, get_sprite_sheet_handle};
+
+/// How long the AssetCache holds on to an asset
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AssetScope {
+    /// kept for the whole session: ships, lasers, effects and sounds
+    Global,
+    /// kept until the match ends: an arena's background and music
+    Arena,
+}
+
+struct Cached<H> {
+    handle: H,
+    scope: AssetScope,
+}
+
+/// Holds a handle to every asset loaded so far, keyed by path, so each one is only
+/// read from disk once however many states ask for it. Handles are reference counted,
+/// so once the cache lets go of an arena asset its data is freed as soon as the last
+/// entity drawing it is deleted.
+#[derive(Default)]
+pub struct AssetCache {
+    sprite_sheets: HashMap<String, Cached<SpriteSheetHandle>>,
+    prefabs: HashMap<String, Cached<Handle<Prefab<AnimationPrefabData>>>>,
+    sounds: HashMap<String, Cached<SourceHandle>>,
+}
+
+impl AssetCache {
+    /// Drops every asset held for `scope`, returning how many were let go
+    pub fn release(&mut self, scope: AssetScope) -> usize {
+        let before = self.len();
+
+        self.sprite_sheets.retain(|_, cached| cached.scope != scope);
+        self.prefabs.retain(|_, cached| cached.scope != scope);
+        self.sounds.retain(|_, cached| cached.scope != scope);
+
+        let released = before - self.len();
+        if released > 0 {
+            log::info!("Released {} {:?} assets", released, scope);
+        }
+        released
+    }
+
+    /// Number of assets currently held
+    fn len(&self) -> usize {
+        self.sprite_sheets.len() + self.prefabs.len() + self.sounds.len()
+    }
+}
+
+/// Returns the cached handle for `key`. An arena asset that is asked for globally
+/// is promoted so it outlives the match.
+fn hit<H: Clone>(cache: &mut HashMap<String, Cached<H>>, key: &str, scope: AssetScope) -> Option<H> {
+    cache.get_mut(key).map(|cached| {
+        log::debug!("Reusing {}", key);
+        if scope == AssetScope::Global {
+            cached.scope = AssetScope::Global;
+        }
+        cached.handle.clone()
+    })
+}
+
+fn cached<H>(handle: H, scope: AssetScope) -> Cached<H> {
+    Cached { handle, scope }
+}
+
+/// Loads a sprite sheet through the AssetCache, reusing the handle if it is already loaded
+pub fn cached_sprite_sheet(
+    world: &mut World,
+    texture_path: &str,
+    ron_path: &str,
+    scope: AssetScope,
+    progress_counter: &mut ProgressCounter,
+) -> SpriteSheetHandle {
+    // the same ron can describe several textures, so both paths make the key
+    let key = format!("{}:{}", texture_path, ron_path);
+
+    {
+        let mut cache = world.entry::<AssetCache>().or_insert_with(AssetCache::default);
+        if let Some(handle) = hit(&mut cache.sprite_sheets, &key, scope) {
+            return handle;
+        }
+    }
+
+    let handle = get_sprite_sheet_handle(world, texture_path, ron_path, progress_counter);
+    world.write_resource::<AssetCache>().sprite_sheets.insert(key, cached(handle.clone(), scope));
+    handle
+}
+
+/// Loads an animation prefab through the AssetCache, reusing the handle if it is already loaded
+pub fn cached_animation_prefab(
+    world: &mut World,
+    ron_path: &str,
+    scope: AssetScope,
+    progress_counter: &mut ProgressCounter,
+) -> Handle<Prefab<AnimationPrefabData>> {
+    {
+        let mut cache = world.entry::<AssetCache>().or_insert_with(AssetCache::default);
+        if let Some(handle) = hit(&mut cache.prefabs, ron_path, scope) {
+            return handle;
+        }
+    }
+
+    let handle = get_animation_prefab_handle(world, ron_path, progress_counter);
+    world.write_resource::<AssetCache>().prefabs.insert(ron_path.to_string(), cached(handle.clone(), scope));
+    handle
+}
+
+/// Loads an ogg track through the AssetCache, reusing the handle if it is already loaded
+pub fn cached_sound(
+    world: &mut World,
+    file: &str,
+    scope: AssetScope,
+    progress_counter: &mut ProgressCounter,
+) -> SourceHandle {
+    {
+        let mut cache = world.entry::<AssetCache>().or_insert_with(AssetCache::default);
+        if let Some(handle) = hit(&mut cache.sounds, file, scope) {
+            return handle;
+        }
+    }
+
+    let handle = {
+        let loader = world.read_resource::<Loader>();
+        loader.load(file, OggFormat, progress_counter, &world.read_resource::<AssetStorage<Source>>())
+    };
+    world.write_resource::<AssetCache>().sounds.insert(file.to_string(), cached(handle.clone(), scope));
+    handle
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use amethyst::assets::ProgressCounter;
+
+/// The kinds of asset the manifest lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    SpriteSheet,
+    Prefab,
+    Sound,
+}
+
+impl fmt::Display for AssetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetKind::SpriteSheet => write!(f, "sprite sheet"),
+            AssetKind::Prefab => write!(f, "prefab"),
+            AssetKind::Sound => write!(f, "sound"),
+        }
+    }
+}
+
+/// An asset that could not be used. Sprite sheets are drawn with the placeholder instead,
+/// prefabs are skipped and sounds stay silent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssetError {
+    /// an id asked for by the game, a ship or an arena that the manifest does not list
+    UnknownId { kind: AssetKind, id: String },
+    /// a file that is missing, or could not be read or parsed
+    LoadFailed { path: String, error: String },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::UnknownId { kind, id } => write!(f, "no {} called {} in the asset manifest", kind, id),
+            AssetError::LoadFailed { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+/// AssetErrors collects every asset failure so far. Each one is logged the first time it is
+/// reported, and files that failed are not loaded again.
+#[derive(Default)]
+pub struct AssetErrors {
+    errors: Vec<AssetError>,
+    failed_paths: HashSet<String>,
+    // errors before this index have already been shown on a loading screen
+    shown: usize,
+}
+
+impl AssetErrors {
+    /// Records `error`, logging it if it has not been seen before
+    pub fn report(&mut self, error: AssetError) {
+        if self.errors.contains(&error) {
+            return;
+        }
+
+        log::error!("Asset error, {}", error);
+
+        if let AssetError::LoadFailed { path, .. } = &error {
+            self.failed_paths.insert(path.clone());
+        }
+        self.errors.push(error);
+    }
+
+    /// Records every failure a finished ProgressCounter saw
+    pub fn report_progress(&mut self, progress_counter: &ProgressCounter) {
+        for failure in progress_counter.errors() {
+            self.report(AssetError::LoadFailed {
+                path: failure.asset_name.clone(),
+                error: failure.error.to_string(),
+            });
+        }
+    }
+
+    /// True if the file at `path` has already failed to load
+    pub fn failed(&self, path: &str) -> bool {
+        self.failed_paths.contains(path)
+    }
+
+    /// Errors reported since the last call, for the loading screen to list
+    pub fn take_unshown(&mut self) -> Vec<AssetError> {
+        let unshown = self.errors[self.shown..].to_vec();
+        self.shown = self.errors.len();
+        unshown
+    }
+}
\ No newline at end of file
@@ -6,7 +6,7 @@ use amethyst::{
     assets::{Handle, Prefab, PrefabLoader, RonFormat, ProgressCounter},
     assets::{AssetStorage, Loader},
     renderer::{
-        sprite::SpriteSheetHandle,
+        sprite::SpriteSheetHandle, SpriteRender,
         ImageFormat, SpriteSheet, 
             SpriteSheetFormat, Texture,
 
@@ -15,91 +15,239 @@ use amethyst::{
 };
 
 use crate::components::{AnimationPrefabData};
+use super::asset_cache::{cached_animation_prefab, cached_sprite_sheet, AssetScope};
+use super::asset_errors::{AssetError, AssetErrors, AssetKind};
+use super::manifest::{AssetManifest, SpriteSheetEntry};
+use super::{ArenaDefinition, Arenas, ShipDefinitions, REQUIRED_SHIPS, SHIP_DIR};
+use crate::audio::{load_music_track, MUSIC_TRACKS, SOUND_EFFECTS};
 
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
-pub enum AssetType {
-    //Background,
-    LaserLight,
-    LaserDark,
-    LaserImpact,
-    Thrust,
-    LightShip,
-    DarkShip,
-}
+// manifest ids of the sprites the game draws itself; ships and arenas name their own
+pub const SPRITE_THRUST: &str = "thrust";
+pub const SPRITE_PLANET: &str = "planet";
+pub const SPRITE_ASTEROID: &str = "asteroid";
+// plain white square, scaled and tinted for overlays
+pub const SPRITE_PIXEL: &str = "pixel";
+// white starfield sprites, tinted per layer
+pub const SPRITE_STAR: &str = "star";
+pub const SPRITE_NEBULA: &str = "nebula";
+// soft white dot, tinted by each particle
+pub const SPRITE_PARTICLE: &str = "particle";
+// white arrowhead pointing up, for off-screen indicators
+pub const SPRITE_INDICATOR: &str = "indicator";
+pub const PREFAB_LASER_IMPACT: &str = "laser_impact";
+
+pub const ENGINE_SPRITE_SHEETS: &[&str] = &[
+    SPRITE_THRUST,
+    SPRITE_PLANET,
+    SPRITE_ASTEROID,
+    SPRITE_PIXEL,
+    SPRITE_STAR,
+    SPRITE_NEBULA,
+    SPRITE_PARTICLE,
+    SPRITE_INDICATOR,
+];
+
+// magenta checkerboard drawn in place of any sprite sheet that is missing or failed to load
+pub const PLACEHOLDER_TEXTURE: &str = "texture/missing.png";
+pub const PLACEHOLDER_SPRITE_SHEET: &str = "texture/missing.ron";
 
 #[derive(Default)]
 pub struct SpriteSheetList {
-    sprite_sheets: HashMap<AssetType, SpriteSheetHandle>,
+    sprite_sheets: HashMap<String, SpriteSheetHandle>,
+    placeholder: Option<SpriteSheetHandle>,
 }
 
 impl SpriteSheetList {
-    pub fn insert(&mut self, asset_type: AssetType, sprite_sheet_handle: SpriteSheetHandle) {
-        self.sprite_sheets.insert(asset_type, sprite_sheet_handle);
+    pub fn insert(&mut self, id: &str, sprite_sheet_handle: SpriteSheetHandle) {
+        self.sprite_sheets.insert(id.to_string(), sprite_sheet_handle);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.sprite_sheets.remove(id);
+    }
+
+    pub fn set_placeholder(&mut self, sprite_sheet_handle: SpriteSheetHandle) {
+        self.placeholder = Some(sprite_sheet_handle);
     }
 
-    pub fn get(&self, asset_type: AssetType) -> Option<&SpriteSheetHandle> {
-        self.sprite_sheets.get(&asset_type)
+    /// The sprite sheet for `id`, or the placeholder if it is missing or failed to load
+    pub fn get(&self, id: &str) -> Option<&SpriteSheetHandle> {
+        self.sprite_sheets.get(id).or_else(|| self.placeholder.as_ref())
+    }
+
+    /// A SpriteRender of the first sprite in the sheet for `id`, as `get` finds it
+    pub fn render(&self, id: &str) -> Option<SpriteRender> {
+        self.get(id).map(|sprite_sheet| SpriteRender {
+            sprite_sheet: sprite_sheet.clone(),
+            sprite_number: 0,
+        })
     }
 }
 
 #[derive(Default)]
 pub struct PrefabList {
-    prefabs: HashMap<AssetType, Handle<Prefab<AnimationPrefabData>>>,
+    prefabs: HashMap<String, Handle<Prefab<AnimationPrefabData>>>,
 }
 
 impl PrefabList {
     pub fn insert(
         &mut self,
-        asset_type: AssetType,
+        id: &str,
         prefab_handle: Handle<Prefab<AnimationPrefabData>>,
     ) {
-        self.prefabs.insert(asset_type, prefab_handle);
+        self.prefabs.insert(id.to_string(), prefab_handle);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.prefabs.remove(id);
     }
 
-    pub fn get(&self, asset_type: AssetType) -> Option<&Handle<Prefab<AnimationPrefabData>>> {
-        self.prefabs.get(&asset_type)
+    pub fn get(&self, id: &str) -> Option<&Handle<Prefab<AnimationPrefabData>>> {
+        self.prefabs.get(id)
     }
 }
 
-/// Loads SpriteSheetHandle's for all the assets in teh AssetType list into the world
-pub fn load_assets(world: &mut World, asset_type_list: Vec<AssetType>) -> ProgressCounter {
-    let mut sprite_sheet_list = SpriteSheetList::default();
-    let mut prefab_list = PrefabList::default();
+/// Loads every preloaded sprite sheet and prefab in the AssetManifest into the world.
+/// Assets already in the AssetCache are reused, and files that failed before are skipped,
+/// so the returned ProgressCounter only counts what actually had to be read from disk.
+pub fn load_assets(world: &mut World) -> ProgressCounter {
     let mut progress_counter = ProgressCounter::new();
+    let manifest = world.read_resource::<AssetManifest>().clone();
 
-    for &asset_type in asset_type_list.iter() {
-        let (texture_path, ron_path) = match asset_type {
-            AssetType::LaserImpact => ("texture/small_explosion.png", "prefab/small_explosion.ron"),
-            AssetType::Thrust => ("texture/thrust.png", "texture/thrust.ron"),
-            AssetType::LaserLight => ("texture/bullet.png", "texture/bullet.ron"),
-            AssetType::LaserDark => ("texture/dark_bullet.png", "texture/bullet.ron"),
-            AssetType::LightShip => ("texture/ship_spritesheet.png", "texture/ship_spritesheet.ron"),
-            AssetType::DarkShip => ("texture/dark_ship_spritesheet.png", "texture/dark_ship_spritesheet.ron"),
-        };
-
-        match asset_type {
-            AssetType::Thrust
-            | AssetType::LaserLight 
-            | AssetType::LaserDark
-            | AssetType::LightShip
-            | AssetType::DarkShip => {
-                let sprite_sheet_handle = 
-                    get_sprite_sheet_handle(world, texture_path, ron_path, &mut progress_counter);
-                sprite_sheet_list.insert(asset_type, sprite_sheet_handle);
-            }
-            AssetType::LaserImpact => {
-                let prefab_handle = get_animation_prefab_handle(world, ron_path, &mut progress_counter);
-                prefab_list.insert(asset_type, prefab_handle);
-            }
-        };
-    };
+    // added to, not replaced, so handles held by earlier states stay valid
+    world.entry::<SpriteSheetList>().or_insert_with(SpriteSheetList::default);
+    world.entry::<PrefabList>().or_insert_with(PrefabList::default);
+    world.entry::<AssetErrors>().or_insert_with(AssetErrors::default);
+
+    report_unknown_ids(world, &manifest);
+    report_missing_ships(world);
+
+    let placeholder = cached_sprite_sheet(
+        world, PLACEHOLDER_TEXTURE, PLACEHOLDER_SPRITE_SHEET, AssetScope::Global, &mut progress_counter);
+    world.write_resource::<SpriteSheetList>().set_placeholder(placeholder);
+
+    for (id, entry) in manifest.sprite_sheets.iter().filter(|(_, entry)| entry.preload) {
+        if sprite_sheet_failed(world, entry) {
+            continue;
+        }
+        let sprite_sheet_handle = cached_sprite_sheet(
+            world, &entry.texture, &entry.sprite_sheet, AssetScope::Global, &mut progress_counter);
+        world.write_resource::<SpriteSheetList>().insert(id, sprite_sheet_handle);
+    }
+
+    for (id, ron_path) in manifest.prefabs.iter() {
+        if world.read_resource::<AssetErrors>().failed(ron_path) {
+            continue;
+        }
+        let prefab_handle = cached_animation_prefab(
+            world, ron_path, AssetScope::Global, &mut progress_counter);
+        world.write_resource::<PrefabList>().insert(id, prefab_handle);
+    }
 
-    world.insert(sprite_sheet_list);
-    world.insert(prefab_list);
     progress_counter
 }
 
-fn get_animation_prefab_handle(
+/// Loads the background and music of `arena` into the AssetCache, held only until the match ends.
+/// The LoadingState calls this before a match, so the Game finds them already cached.
+pub fn load_arena_assets(world: &mut World, arena: &ArenaDefinition, progress_counter: &mut ProgressCounter) {
+    if let Some(background) = arena.background.as_ref() {
+        // an unknown id is reported by load_assets
+        let entry = world.read_resource::<AssetManifest>().sprite_sheet(&background.sprite_sheet).cloned();
+
+        if let Some(entry) = entry.filter(|entry| !sprite_sheet_failed(world, entry)) {
+            cached_sprite_sheet(world, &entry.texture, &entry.sprite_sheet, AssetScope::Arena, progress_counter);
+        }
+    }
+
+    if let Some(music) = arena.music.as_ref() {
+        load_music_track(world, music, progress_counter);
+    }
+}
+
+/// Takes out of the lists every asset whose file has failed to load, so sprites fall back
+/// to the placeholder and prefabs are skipped. Called once loading has finished.
+pub fn drop_failed_assets(world: &mut World) {
+    let manifest = world.read_resource::<AssetManifest>().clone();
+
+    for (id, entry) in manifest.sprite_sheets.iter() {
+        if sprite_sheet_failed(world, entry) {
+            world.write_resource::<SpriteSheetList>().remove(id);
+        }
+    }
+
+    for (id, ron_path) in manifest.prefabs.iter() {
+        if world.read_resource::<AssetErrors>().failed(ron_path) {
+            world.write_resource::<PrefabList>().remove(id);
+        }
+    }
+}
+
+fn sprite_sheet_failed(world: &World, entry: &SpriteSheetEntry) -> bool {
+    let errors = world.read_resource::<AssetErrors>();
+    errors.failed(&entry.texture) || errors.failed(&entry.sprite_sheet)
+}
+
+/// Reports every sprite sheet the game, the ships and the arenas ask for that the
+/// manifest does not list. Those are drawn with the placeholder.
+fn report_unknown_ids(world: &mut World, manifest: &AssetManifest) {
+    let unknown = unknown_ids(
+        manifest,
+        &world.read_resource::<ShipDefinitions>(),
+        &world.read_resource::<Arenas>(),
+    );
+
+    let mut errors = world.write_resource::<AssetErrors>();
+    for error in unknown {
+        errors.report(error);
+    }
+}
+
+/// Reports every ship definition a match needs that did not load. A stand-in flies in its place.
+fn report_missing_ships(world: &mut World) {
+    let missing: Vec<&str> = {
+        let ship_definitions = world.read_resource::<ShipDefinitions>();
+        REQUIRED_SHIPS.iter().copied().filter(|name| ship_definitions.get(name).is_none()).collect()
+    };
+
+    let mut errors = world.write_resource::<AssetErrors>();
+    for name in missing {
+        errors.report(AssetError::LoadFailed {
+            path: format!("{}/{}.ron", SHIP_DIR, name),
+            error: "missing or unable to parse, using a stand-in ship".to_string(),
+        });
+    }
+}
+
+/// Every id the game, the ships and the arenas refer to that the manifest does not list
+pub fn unknown_ids(manifest: &AssetManifest, ship_definitions: &ShipDefinitions, arenas: &Arenas) -> Vec<AssetError> {
+    let mut sprite_sheets: Vec<String> = ENGINE_SPRITE_SHEETS.iter().map(|id| id.to_string()).collect();
+    let mut sounds: Vec<String> = SOUND_EFFECTS.iter().chain(MUSIC_TRACKS.iter()).map(|id| id.to_string()).collect();
+
+    for definition in ship_definitions.definitions() {
+        sprite_sheets.push(definition.sprite_sheet.clone());
+        sprite_sheets.push(definition.laser_sprite_sheet.clone());
+    }
+    for arena in arenas.definitions() {
+        if let Some(background) = arena.background.as_ref() {
+            sprite_sheets.push(background.sprite_sheet.clone());
+        }
+        sounds.extend(arena.music.clone());
+    }
+
+    let mut unknown = Vec::new();
+    for id in sprite_sheets.into_iter().filter(|id| manifest.sprite_sheet(id).is_none()) {
+        unknown.push(AssetError::UnknownId { kind: AssetKind::SpriteSheet, id });
+    }
+    for id in sounds.into_iter().filter(|id| manifest.sound(id).is_none()) {
+        unknown.push(AssetError::UnknownId { kind: AssetKind::Sound, id });
+    }
+    if manifest.prefab(PREFAB_LASER_IMPACT).is_none() {
+        unknown.push(AssetError::UnknownId { kind: AssetKind::Prefab, id: PREFAB_LASER_IMPACT.to_string() });
+    }
+    unknown
+}
+
+pub fn get_animation_prefab_handle(
     world: &mut World,
     ron_path: &str,
     progress_counter: &mut ProgressCounter,
@@ -119,7 +267,8 @@ pub fn get_sprite_sheet_handle(
     let texture_handle = {
         let loader = &world.read_resource::<Loader>();
         let texture_storage = &world.read_resource::<AssetStorage<Texture>>();
-        loader.load(texture_path, ImageFormat::default(), (), &texture_storage)
+        // counted too, so a missing image is reported rather than drawn blank
+        loader.load(texture_path, ImageFormat::default(), &mut *progress_counter, &texture_storage)
     };
     let loader = &world.read_resource::<Loader>();
     let sprite_sheet_store = &world.read_resource::<AssetStorage<SpriteSheet>>();
@@ -0,0 +1,94 @@
+use amethyst::{core::math::Vector2, ecs::Entity};
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::components::{DamageReport, Side};
+
+/// Round results kept in the kill feed
+pub const KILL_FEED_LENGTH: usize = 5;
+
+/// What a ship was hit with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weapon {
+    Laser,
+    Ram,
+    Asteroid,
+    Planet,
+    Wall,
+    DangerZone,
+}
+
+impl Weapon {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Weapon::Laser => "LASER",
+            Weapon::Ram => "RAM",
+            Weapon::Asteroid => "ASTEROID",
+            Weapon::Planet => "PLANET",
+            Weapon::Wall => "WALL",
+            Weapon::DangerZone => "DANGER ZONE",
+        }
+    }
+}
+
+/// Hit is one landed blow, at the arena position it struck
+#[derive(Debug, Clone, Copy)]
+pub struct Hit {
+    pub x: f32,
+    pub y: f32,
+    pub report: DamageReport,
+    pub weapon: Weapon,
+}
+
+/// Kill is a round result for the kill feed. Hazards kill without a `killer`.
+#[derive(Debug, Clone)]
+pub struct Kill {
+    pub victim: (String, Side),
+    pub killer: Option<(String, Side)>,
+    pub weapon: Weapon,
+}
+
+/// Wreck is a ship destroyed this frame, where it blew up and how fast it was moving
+#[derive(Debug, Clone, Copy)]
+pub struct Wreck {
+    pub x: f32,
+    pub y: f32,
+    pub velocity: Vector2<f32>,
+}
+
+/// CombatLog collects every hit landed this frame, for the floating damage numbers,
+/// and every ship destroyed this frame, for the wreckage and the screen shake.
+/// It also remembers what last hit each ship, and keeps the most recent kills for the kill feed.
+#[derive(Default)]
+pub struct CombatLog {
+    pub hits: Vec<Hit>,
+    // refilled by the WinnerSystem every frame, and only read after it
+    pub wrecks: Vec<Wreck>,
+    pub kills: VecDeque<Kill>,
+    last_hits: HashMap<Entity, (Weapon, Option<Side>)>,
+}
+
+impl CombatLog {
+    /// Records a hit on `ship` at (x, y), crediting the `attacker` side if there was one
+    pub fn hit(&mut self, ship: Entity, attacker: Option<Side>, x: f32, y: f32, report: DamageReport, weapon: Weapon) {
+        self.hits.push(Hit { x, y, report, weapon });
+        self.last_hits.insert(ship, (weapon, attacker));
+    }
+
+    /// What last hit `ship`, and which side was behind it
+    pub fn last_hit(&self, ship: Entity) -> Option<(Weapon, Option<Side>)> {
+        self.last_hits.get(&ship).cloned()
+    }
+
+    pub fn kill(&mut self, kill: Kill) {
+        self.kills.push_back(kill);
+        while self.kills.len() > KILL_FEED_LENGTH {
+            self.kills.pop_front();
+        }
+    }
+
+    /// Forgets who hit whom, for a fresh round
+    pub fn new_round(&mut self) {
+        self.last_hits.clear();
+    }
+}
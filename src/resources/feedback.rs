@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+
+/// File under `config` that the feedback settings are loaded from and saved to
+pub const FEEDBACK_CONFIG: &str = "feedback.ron";
+
+// damage that shakes the screen as hard as it will go
+const FULL_SHAKE_DAMAGE: f32 = 100.0;
+// hits at least this heavy freeze the action for a moment
+const HIT_STOP_DAMAGE: f32 = 30.0;
+// seconds of real time a hit-stop lasts
+const HIT_STOP_TIME: f32 = 0.08;
+
+/// Impact of a ship blowing up, as if it were a hit of this much damage
+pub const EXPLOSION_IMPACT: f32 = 80.0;
+
+/// FeedbackSettings turns the screen shake and hit-stop on and off,
+/// for players who find them uncomfortable
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct FeedbackSettings {
+    #[serde(default = "enabled")]
+    pub screen_shake: bool,
+    #[serde(default = "enabled")]
+    pub hit_stop: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for FeedbackSettings {
+    fn default() -> Self {
+        FeedbackSettings {
+            screen_shake: true,
+            hit_stop: true,
+        }
+    }
+}
+
+impl FeedbackSettings {
+    /// True if either effect is on
+    pub fn any_enabled(&self) -> bool {
+        self.screen_shake || self.hit_stop
+    }
+}
+
+/// ScreenShake collects the impacts of a frame.
+/// `trauma` runs from 0 to 1 and shakes the camera by its square, so small hits barely register.
+#[derive(Debug, Default)]
+pub struct ScreenShake {
+    pub trauma: f32,
+    // real seconds left on the current hit-stop
+    pub hit_stop: f32,
+}
+
+impl ScreenShake {
+    /// Adds an impact roughly the size of the damage it did.
+    /// Hits, collisions and explosions all report here, whether or not the effects are enabled.
+    pub fn impact(&mut self, magnitude: f32) {
+        self.trauma = (self.trauma + magnitude / FULL_SHAKE_DAMAGE).min(1.0);
+
+        if magnitude >= HIT_STOP_DAMAGE {
+            self.hit_stop = self.hit_stop.max(HIT_STOP_TIME);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use amethyst::config::Config;
+
+use serde::{Serialize, Deserialize};
+
+use super::AssetKind;
+
+/// File under `assets` that the asset manifest is loaded from
+pub const MANIFEST_FILE: &str = "manifest.ron";
+
+/// SpriteSheetEntry names the texture and sprite sheet ron that make up one sprite sheet.
+/// Entries with `preload` off are loaded when first used instead of by the LoadingState.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpriteSheetEntry {
+    pub texture: String,
+    pub sprite_sheet: String,
+    #[serde(default = "default_true")]
+    pub preload: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// AssetManifest lists every asset the game can load by id, as loaded from `assets/manifest.ron`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AssetManifest {
+    #[serde(default)]
+    pub sprite_sheets: BTreeMap<String, SpriteSheetEntry>,
+    // animation prefab rons
+    #[serde(default)]
+    pub prefabs: BTreeMap<String, String>,
+    // ogg files, for sound effects and music alike
+    #[serde(default)]
+    pub sounds: BTreeMap<String, String>,
+}
+
+impl AssetManifest {
+    /// Loads the manifest at `path`. A manifest that fails to parse is logged and left empty.
+    pub fn load_or_empty(path: &Path) -> AssetManifest {
+        match AssetManifest::load(path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                log::error!("Unable to load asset manifest {:?}: {}", path, e);
+                AssetManifest::default()
+            }
+        }
+    }
+
+    pub fn sprite_sheet(&self, id: &str) -> Option<&SpriteSheetEntry> {
+        self.sprite_sheets.get(id)
+    }
+
+    pub fn prefab(&self, id: &str) -> Option<&str> {
+        self.prefabs.get(id).map(String::as_str)
+    }
+
+    pub fn sound(&self, id: &str) -> Option<&str> {
+        self.sounds.get(id).map(String::as_str)
+    }
+
+    /// Every id the manifest lists, tagged with its kind
+    pub fn ids(&self) -> impl Iterator<Item = (AssetKind, &str)> {
+        let sprite_sheets = self.sprite_sheets.keys().map(|id| (AssetKind::SpriteSheet, id.as_str()));
+        let prefabs = self.prefabs.keys().map(|id| (AssetKind::Prefab, id.as_str()));
+        let sounds = self.sounds.keys().map(|id| (AssetKind::Sound, id.as_str()));
+
+        sprite_sheets.chain(prefabs).chain(sounds)
+    }
+
+    /// Prefixes every file path with `dir`, for a manifest whose paths are relative to somewhere other than `assets`
+    pub fn relative_to(mut self, dir: &str) -> AssetManifest {
+        let prefix = |path: &mut String| *path = format!("{}/{}", dir, path);
+
+        for entry in self.sprite_sheets.values_mut() {
+            prefix(&mut entry.texture);
+            prefix(&mut entry.sprite_sheet);
+        }
+        self.prefabs.values_mut().for_each(prefix);
+        self.sounds.values_mut().for_each(prefix);
+        self
+    }
+
+    /// Adds every entry in `other`, replacing any with the same id
+    pub fn extend(&mut self, other: AssetManifest) {
+        self.sprite_sheets.extend(other.sprite_sheets);
+        self.prefabs.extend(other.prefabs);
+        self.sounds.extend(other.sounds);
+    }
+}
@@ -1,3 +1,22 @@
 pub use self::assets::*;
+pub use self::asset_cache::*;
+pub use self::asset_errors::*;
+pub use self::manifest::*;
+pub use self::mods::*;
+pub use self::scripting::*;
+pub use self::ships::*;
+pub use self::arenas::*;
+pub use self::feedback::*;
+pub use self::combat_log::*;
 
-pub mod assets;
\ No newline at end of file
+pub mod assets;
+pub mod scripting;
+pub mod ships;
+pub mod arenas;
+
+pub mod feedback;
+pub mod combat_log;
+pub mod asset_cache;
+pub mod asset_errors;
+pub mod manifest;
+pub mod mods;
\ No newline at end of file
@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use amethyst::config::Config;
+
+use serde::{Serialize, Deserialize};
+
+use super::{AiScripts, AI_SCRIPT_DIR, Arenas, ARENA_DIR, AssetKind, AssetManifest, MANIFEST_FILE,
+    ShipDefinitions, SHIP_DIR};
+
+/// Directory next to `assets` that content packs are loaded from
+pub const MOD_DIR: &str = "mods";
+
+/// File under `config` that the enabled packs are loaded from and saved to
+pub const MODS_CONFIG: &str = "mods.ron";
+
+// file in each pack's directory describing the pack
+const PACK_FILE: &str = "pack.ron";
+
+/// Directory of the pack `id`, relative to `assets`, which is where the loader reads files from
+pub fn pack_asset_dir(id: &str) -> String {
+    format!("../{}/{}", MOD_DIR, id)
+}
+
+/// PackManifest describes a content pack, as loaded from its `pack.ron`.
+/// Packs load in ascending `priority`, then by directory name, and a later pack wins any id
+/// that an earlier one also provides.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PackManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// ModSettings lists the packs turned off in the mods screen, by directory name.
+/// Anything not listed is on, so a newly installed pack is used straight away.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ModSettings {
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl ModSettings {
+    pub fn is_enabled(&self, id: &str) -> bool {
+        !self.disabled.iter().any(|disabled| disabled == id)
+    }
+}
+
+/// A pack found in the mods directory
+#[derive(Debug, Clone)]
+pub struct ModPack {
+    // name of the pack's directory
+    pub id: String,
+    pub manifest: PackManifest,
+    pub enabled: bool,
+}
+
+/// The kinds of content a pack can provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentKind {
+    Asset(AssetKind),
+    Ship,
+    Arena,
+    AiScript,
+}
+
+impl fmt::Display for ContentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentKind::Asset(kind) => write!(f, "{}", kind),
+            ContentKind::Ship => write!(f, "ship"),
+            ContentKind::Arena => write!(f, "arena"),
+            ContentKind::AiScript => write!(f, "AI script"),
+        }
+    }
+}
+
+/// Two enabled packs providing the same id. The one later in the load order is used.
+#[derive(Debug, Clone)]
+pub struct ModConflict {
+    pub kind: ContentKind,
+    pub id: String,
+    pub overridden: String,
+    pub winner: String,
+}
+
+impl fmt::Display for ModConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} from {} is replaced by {}", self.kind, self.id, self.overridden, self.winner)
+    }
+}
+
+/// ModPacks holds every pack found in the mods directory, in load order,
+/// along with the conflicts between the enabled ones
+#[derive(Debug, Default)]
+pub struct ModPacks {
+    dir: PathBuf,
+    settings: ModSettings,
+    pub packs: Vec<ModPack>,
+    pub conflicts: Vec<ModConflict>,
+    // set once the player turns a pack on or off, which only takes effect on restart
+    pub changed: bool,
+}
+
+impl ModPacks {
+    /// Finds every pack in `dir` and sorts them into load order.
+    /// Directories without a readable `pack.ron` are logged and skipped.
+    pub fn scan(dir: &Path, settings: ModSettings) -> ModPacks {
+        let mut mod_packs = ModPacks {
+            dir: dir.to_path_buf(),
+            settings,
+            ..Default::default()
+        };
+
+        // no mods directory just means no packs are installed
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return mod_packs,
+        };
+
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if !path.is_dir() {
+                continue;
+            }
+
+            let id = match path.file_name().and_then(|name| name.to_str()) {
+                Some(id) if is_plain_name(id) => id.to_string(),
+                _ => {
+                    log::warn!("Skipping content pack {:?}, its directory name is not a plain name", path);
+                    continue;
+                }
+            };
+
+            match PackManifest::load(path.join(PACK_FILE)) {
+                Ok(manifest) => {
+                    let enabled = mod_packs.settings.is_enabled(&id);
+                    mod_packs.packs.push(ModPack { id, manifest, enabled });
+                }
+                Err(e) => log::warn!("Unable to load content pack {:?}: {}", path, e),
+            }
+        }
+
+        mod_packs.packs.sort_by(|a, b| {
+            a.manifest.priority.cmp(&b.manifest.priority).then_with(|| a.id.cmp(&b.id))
+        });
+
+        mod_packs
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = &ModPack> {
+        self.packs.iter().filter(|pack| pack.enabled)
+    }
+
+    /// Loads every enabled pack in order over the base content. A pack replaces any asset,
+    /// ship, arena or AI script with the same id and adds the rest.
+    pub fn apply(
+        &mut self,
+        asset_manifest: &mut AssetManifest,
+        ship_definitions: &mut ShipDefinitions,
+        arenas: &mut Arenas,
+        ai_scripts: &mut AiScripts,
+    ) {
+        let base: HashSet<(ContentKind, String)> =
+            content_ids(asset_manifest, ship_definitions, arenas, ai_scripts).into_iter().collect();
+        // which pack each id was last taken from
+        let mut owners: HashMap<(ContentKind, String), String> = HashMap::new();
+        let mut conflicts = Vec::new();
+
+        for pack in self.enabled() {
+            let pack_dir = self.dir.join(&pack.id);
+
+            // pack manifests name files inside the pack, but the loader reads from `assets`
+            let pack_manifest = if pack_dir.join(MANIFEST_FILE).exists() {
+                let mut pack_manifest = AssetManifest::load_or_empty(&pack_dir.join(MANIFEST_FILE));
+                drop_escaping_paths(&pack.id, &mut pack_manifest);
+                pack_manifest.relative_to(&pack_asset_dir(&pack.id))
+            } else {
+                AssetManifest::default()
+            };
+            let pack_ships = load_if_present(&pack_dir.join(SHIP_DIR), ShipDefinitions::load);
+            let pack_arenas = load_if_present(&pack_dir.join(ARENA_DIR), Arenas::load);
+            let pack_scripts = load_if_present(&pack_dir.join(AI_SCRIPT_DIR), AiScripts::load);
+
+            for key in content_ids(&pack_manifest, &pack_ships, &pack_arenas, &pack_scripts) {
+                match owners.insert(key.clone(), pack.id.clone()) {
+                    Some(overridden) => {
+                        let conflict = ModConflict {
+                            kind: key.0,
+                            id: key.1,
+                            overridden,
+                            winner: pack.id.clone(),
+                        };
+                        log::warn!("Content pack conflict, {}", conflict);
+                        conflicts.push(conflict);
+                    }
+                    None if base.contains(&key) => {
+                        log::info!("Content pack {} replaces {} {}", pack.id, key.0, key.1);
+                    }
+                    None => {}
+                }
+            }
+
+            asset_manifest.extend(pack_manifest);
+            ship_definitions.extend(pack_ships);
+            arenas.extend(pack_arenas);
+            ai_scripts.extend(pack_scripts);
+
+            log::info!("Loaded content pack {} ({} {})", pack.id, pack.manifest.name, pack.manifest.version);
+        }
+
+        self.conflicts = conflicts;
+    }
+
+    /// Turns the pack at `index` on or off. The change is saved by the caller and used from the next start.
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(pack) = self.packs.get_mut(index) {
+            pack.enabled = !pack.enabled;
+
+            self.settings.disabled.retain(|id| id != &pack.id);
+            if !pack.enabled {
+                self.settings.disabled.push(pack.id.clone());
+            }
+            self.changed = true;
+        }
+    }
+
+    pub fn settings(&self) -> &ModSettings {
+        &self.settings
+    }
+}
+
+/// True for a single file or directory name, which cannot point outside the directory it is in
+fn is_plain_name(name: &str) -> bool {
+    is_plain_relative(name) && Path::new(name).components().count() == 1
+}
+
+/// True for a relative path that stays inside the directory it is relative to:
+/// no root, no drive and no `..`
+fn is_plain_relative(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|component| match component {
+        Component::Normal(_) | Component::CurDir => true,
+        _ => false,
+    })
+}
+
+/// Removes every entry of a pack's manifest with a file path that would reach outside the pack
+fn drop_escaping_paths(pack_id: &str, manifest: &mut AssetManifest) {
+    let keep = |id: &str, path: &str| {
+        let plain = is_plain_relative(path);
+        if !plain {
+            log::warn!("Content pack {} asset {} ignored, {} is outside the pack", pack_id, id, path);
+        }
+        plain
+    };
+
+    let sprite_sheets: Vec<String> = manifest.sprite_sheets.iter()
+        .filter(|(id, entry)| !(keep(id, &entry.texture) && keep(id, &entry.sprite_sheet)))
+        .map(|(id, _)| id.clone())
+        .collect();
+    let prefabs: Vec<String> = manifest.prefabs.iter()
+        .filter(|(id, path)| !keep(id, path))
+        .map(|(id, _)| id.clone())
+        .collect();
+    let sounds: Vec<String> = manifest.sounds.iter()
+        .filter(|(id, path)| !keep(id, path))
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for id in sprite_sheets {
+        manifest.sprite_sheets.remove(&id);
+    }
+    for id in prefabs {
+        manifest.prefabs.remove(&id);
+    }
+    for id in sounds {
+        manifest.sounds.remove(&id);
+    }
+}
+
+fn load_if_present<T: Default>(dir: &Path, load: fn(&Path) -> T) -> T {
+    if dir.is_dir() {
+        load(dir)
+    } else {
+        T::default()
+    }
+}
+
+/// Every id the given content provides, tagged with its kind
+fn content_ids(
+    asset_manifest: &AssetManifest,
+    ship_definitions: &ShipDefinitions,
+    arenas: &Arenas,
+    ai_scripts: &AiScripts,
+) -> Vec<(ContentKind, String)> {
+    let assets = asset_manifest.ids()
+        .map(|(kind, id)| (ContentKind::Asset(kind), id.to_string()));
+    let ships = ship_definitions.names().into_iter().map(|id| (ContentKind::Ship, id));
+    let arena_ids = arenas.names().into_iter().map(|id| (ContentKind::Arena, id));
+    let scripts = ai_scripts.names().into_iter().map(|id| (ContentKind::AiScript, id));
+
+    assets.chain(ships).chain(arena_ids).chain(scripts).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_paths_must_stay_inside_the_pack() {
+        assert!(is_plain_relative("texture/red_nebula.png"));
+        assert!(is_plain_relative("./texture/red_nebula.png"));
+
+        assert!(!is_plain_relative("../assets/texture/spaceships.jpg"));
+        assert!(!is_plain_relative("texture/../../secret.png"));
+        assert!(!is_plain_relative("/etc/passwd"));
+        assert!(!is_plain_relative(""));
+    }
+
+    #[test]
+    fn pack_ids_are_single_names() {
+        assert!(is_plain_name("example"));
+
+        assert!(!is_plain_name(".."));
+        assert!(!is_plain_name("example/nested"));
+        assert!(!is_plain_name("/example"));
+    }
+}
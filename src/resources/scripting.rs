@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::packages::{BasicArrayPackage, BasicMapPackage, BasicMathPackage, CorePackage, Package};
+
+use crate::components::ShipControl;
+
+/// Directory under `assets` that AI pilot scripts are loaded from
+pub const AI_SCRIPT_DIR: &str = "ai";
+const AI_SCRIPT_EXTENSION: &str = "rhai";
+
+/// Entry point every AI script must define
+const THINK_FN: &str = "think";
+
+// keep a runaway script from stalling the frame
+const MAX_OPERATIONS: u64 = 20_000;
+const MAX_CALL_LEVELS: usize = 16;
+
+/// ShipState is the view of a ship handed to a script each tick
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ShipState {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub heading: f32,
+    pub radius: f32,
+    pub structure: i32,
+    pub energy: f32,
+    pub max_energy: f32,
+    pub shield: f32,
+}
+
+/// ProjectileState is a laser visible to the script
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProjectileState {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub hostile: bool,
+}
+
+/// ArenaState describes the playing field
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaState {
+    pub width: f32,
+    pub height: f32,
+    // false when the arena has walls instead of wrapping edges
+    pub wraps: bool,
+    // how far the danger zone border has closed in from each edge
+    pub margin: f32,
+}
+
+/// AiScripts holds a sandboxed script engine and every compiled pilot script
+pub struct AiScripts {
+    engine: Engine,
+    scripts: BTreeMap<String, AST>,
+}
+
+impl Default for AiScripts {
+    fn default() -> Self {
+        AiScripts {
+            engine: sandboxed_engine(),
+            scripts: BTreeMap::new(),
+        }
+    }
+}
+
+impl AiScripts {
+    /// Compiles every `.rhai` file in `dir`, keyed by file stem.
+    /// Scripts that fail to compile are logged and skipped.
+    pub fn load(dir: &Path) -> AiScripts {
+        let mut ai_scripts = AiScripts::default();
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Unable to read AI script directory {:?}: {}", dir, e);
+                return ai_scripts;
+            }
+        };
+
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some(AI_SCRIPT_EXTENSION) {
+                continue;
+            }
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            match fs::read_to_string(&path) {
+                Ok(source) => {
+                    if let Err(e) = ai_scripts.insert(&name, &source) {
+                        log::warn!("Unable to compile AI script {:?}: {}", path, e);
+                    }
+                }
+                Err(e) => log::warn!("Unable to read AI script {:?}: {}", path, e),
+            }
+        }
+
+        log::info!("Loaded AI scripts: {:?}", ai_scripts.names());
+        ai_scripts
+    }
+
+    /// Compiles `source` and stores it under `name`, replacing any previous script
+    pub fn insert(&mut self, name: &str, source: &str) -> Result<(), String> {
+        let ast = self.engine.compile(source).map_err(|e| e.to_string())?;
+        self.scripts.insert(name.to_string(), ast);
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.scripts.contains_key(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.scripts.keys().cloned().collect()
+    }
+
+    /// Adds every script in `other`, replacing any with the same name
+    pub fn extend(&mut self, other: AiScripts) {
+        self.scripts.extend(other.scripts);
+    }
+
+    /// Runs the script's `think` function and turns its answer into a ShipControl
+    pub fn think(
+        &self,
+        name: &str,
+        me: &ShipState,
+        enemy: &ShipState,
+        projectiles: &[ProjectileState],
+        arena: &ArenaState,
+    ) -> Result<ShipControl, String> {
+        let ast = self.scripts.get(name)
+            .ok_or_else(|| format!("No AI script named {}", name))?;
+
+        let projectiles: Array = projectiles.iter()
+            .map(|p| Dynamic::from(projectile_map(me, p, arena)))
+            .collect();
+
+        let mut scope = Scope::new();
+        let result: Dynamic = self.engine
+            .call_fn(
+                &mut scope,
+                ast,
+                THINK_FN,
+                (ship_map(me), enemy_map(me, enemy, arena), projectiles, arena_map(arena)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let commands = result.try_cast::<Map>()
+            .ok_or_else(|| format!("AI script {} must return a map from {}", name, THINK_FN))?;
+
+        Ok(ShipControl {
+            rotate: read_f32(&commands, "rotate").max(-1.0).min(1.0),
+            thrust: read_f32(&commands, "thrust").max(0.0).min(1.0),
+            fire: read_bool(&commands, "fire"),
+            boost: read_bool(&commands, "boost"),
+            special: read_bool(&commands, "special"),
+        })
+    }
+}
+
+/// Scripts get maths and data structures but no file, module or time access.
+/// A raw engine starts with no functions at all and with `print` and `debug` discarded,
+/// so only the packages registered here are available.
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new_raw();
+    // core covers operators, arithmetic, comparisons, strings and ranges
+    engine.load_package(CorePackage::new().get());
+    engine.load_package(BasicMathPackage::new().get());
+    engine.load_package(BasicArrayPackage::new().get());
+    engine.load_package(BasicMapPackage::new().get());
+    // `import` fails instead of reading scripts from disk
+    engine.set_module_resolver(None::<DummyModuleResolver>);
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(1024);
+    engine.set_max_map_size(256);
+    engine
+}
+
+fn ship_map(ship: &ShipState) -> Map {
+    let mut map = Map::new();
+    map.insert("x".into(), Dynamic::from(ship.x as f64));
+    map.insert("y".into(), Dynamic::from(ship.y as f64));
+    map.insert("vx".into(), Dynamic::from(ship.vx as f64));
+    map.insert("vy".into(), Dynamic::from(ship.vy as f64));
+    map.insert("heading".into(), Dynamic::from(ship.heading as f64));
+    map.insert("radius".into(), Dynamic::from(ship.radius as f64));
+    map.insert("structure".into(), Dynamic::from(ship.structure as i64));
+    map.insert("energy".into(), Dynamic::from(ship.energy as f64));
+    map.insert("max_energy".into(), Dynamic::from(ship.max_energy as f64));
+    map.insert("shield".into(), Dynamic::from(ship.shield as f64));
+    map
+}
+
+/// The enemy map also carries the wrapped distance and bearing relative to our heading
+fn enemy_map(me: &ShipState, enemy: &ShipState, arena: &ArenaState) -> Map {
+    let mut map = ship_map(enemy);
+    let (dx, dy) = arena.delta(me.x, me.y, enemy.x, enemy.y);
+    map.insert("distance".into(), Dynamic::from((dx * dx + dy * dy).sqrt() as f64));
+    map.insert("bearing".into(), Dynamic::from(relative_bearing(me.heading, dx, dy) as f64));
+    map
+}
+
+fn projectile_map(me: &ShipState, projectile: &ProjectileState, arena: &ArenaState) -> Map {
+    let mut map = Map::new();
+    let (dx, dy) = arena.delta(me.x, me.y, projectile.x, projectile.y);
+    map.insert("x".into(), Dynamic::from(projectile.x as f64));
+    map.insert("y".into(), Dynamic::from(projectile.y as f64));
+    map.insert("vx".into(), Dynamic::from(projectile.vx as f64));
+    map.insert("vy".into(), Dynamic::from(projectile.vy as f64));
+    map.insert("hostile".into(), Dynamic::from(projectile.hostile));
+    map.insert("distance".into(), Dynamic::from((dx * dx + dy * dy).sqrt() as f64));
+    map.insert("bearing".into(), Dynamic::from(relative_bearing(me.heading, dx, dy) as f64));
+    map
+}
+
+fn arena_map(arena: &ArenaState) -> Map {
+    let mut map = Map::new();
+    map.insert("width".into(), Dynamic::from(arena.width as f64));
+    map.insert("height".into(), Dynamic::from(arena.height as f64));
+    map.insert("wraps".into(), Dynamic::from(arena.wraps));
+    map.insert("margin".into(), Dynamic::from(arena.margin as f64));
+    map
+}
+
+impl ArenaState {
+    /// Shortest offset from (ax, ay) to (bx, by), crossing the edges if the arena wraps
+    pub fn delta(&self, ax: f32, ay: f32, bx: f32, by: f32) -> (f32, f32) {
+        if self.wraps {
+            (wrapped_delta(ax, bx, self.width), wrapped_delta(ay, by, self.height))
+        } else {
+            (bx - ax, by - ay)
+        }
+    }
+}
+
+/// Shortest signed distance from `from` to `to` along an axis that wraps at `size`
+pub fn wrapped_delta(from: f32, to: f32, size: f32) -> f32 {
+    let mut delta = (to - from) % size;
+    if delta > size / 2.0 {
+        delta -= size;
+    } else if delta < -size / 2.0 {
+        delta += size;
+    }
+    delta
+}
+
+/// Angle from `heading` to the direction (dx, dy), normalised to -PI..PI.
+/// Positive means the target is counterclockwise.
+fn relative_bearing(heading: f32, dx: f32, dy: f32) -> f32 {
+    let mut bearing = dy.atan2(dx) - heading;
+    while bearing > PI {
+        bearing -= 2.0 * PI;
+    }
+    while bearing < -PI {
+        bearing += 2.0 * PI;
+    }
+    bearing
+}
+
+fn read_f32(map: &Map, key: &str) -> f32 {
+    match map.get(key) {
+        Some(value) => value.as_float()
+            .map(|v| v as f32)
+            .or_else(|_| value.as_int().map(|v| v as f32))
+            .unwrap_or(0.0),
+        None => 0.0,
+    }
+}
+
+fn read_bool(map: &Map, key: &str) -> bool {
+    map.get(key)
+        .and_then(|value| value.as_bool().ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena() -> ArenaState {
+        ArenaState { width: 1600.0, height: 1024.0, wraps: true, margin: 0.0 }
+    }
+
+    fn think(source: &str) -> Result<ShipControl, String> {
+        let mut ai_scripts = AiScripts::default();
+        ai_scripts.insert("test", source)?;
+        ai_scripts.think("test", &ShipState::default(), &ShipState::default(), &[], &arena())
+    }
+
+    #[test]
+    fn scripts_can_use_maths_and_maps() {
+        let control = think("fn think(me, enemy, projectiles, arena) { #{ rotate: (-0.5).abs(), fire: true } }")
+            .expect("script should run");
+
+        assert!((control.rotate - 0.5).abs() < 1e-6);
+        assert!(control.fire);
+    }
+
+    #[test]
+    fn scripts_cannot_import_modules() {
+        assert!(think("fn think(me, enemy, projectiles, arena) { import \"aggressive\" as other; #{} }").is_err());
+    }
+
+    #[test]
+    fn scripts_cannot_read_the_clock() {
+        assert!(think("fn think(me, enemy, projectiles, arena) { let t = timestamp(); #{} }").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use amethyst::config::Config;
+
+use serde::{Serialize, Deserialize};
+
+use crate::components::{Combat, Energy, LaserType, Physical, Shield, Special, SpecialType};
+
+/// Directory under `assets` that ship definitions are loaded from
+pub const SHIP_DIR: &str = "ships";
+
+// the ship definitions every match flies, by file name
+pub const LIGHT_SHIP: &str = "light";
+pub const DARK_SHIP: &str = "dark";
+pub const REQUIRED_SHIPS: &[&str] = &[LIGHT_SHIP, DARK_SHIP];
+
+/// EnergyDefinition is the battery section of a ship definition
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EnergyDefinition {
+    pub max: f32,
+    pub regen_rate: f32,
+    pub laser_cost: f32,
+    pub boost_cost: f32,
+    pub special_cost: f32,
+}
+
+/// SpecialDefinition fills the special ability slot of a ship definition
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpecialDefinition {
+    pub special_type: SpecialType,
+    pub cooldown: f32,
+    #[serde(default)]
+    pub duration: f32,
+}
+
+/// ShieldDefinition is the regenerating shield section of a ship definition
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ShieldDefinition {
+    pub max_strength: f32,
+    pub regen_rate: f32,
+    pub regen_delay: f32,
+    pub laser_effectiveness: f32,
+    pub collision_effectiveness: f32,
+}
+
+/// ShipDefinition holds the stats of a ship as loaded from `assets/ships/*.ron`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ShipDefinition {
+    pub name: String,
+
+    // sprites, by asset manifest id
+    pub sprite_sheet: String,
+    pub laser_sprite_sheet: String,
+
+    // physics
+    pub radius: f32,
+    pub mass: f32,
+    pub acceleration: f32,
+    pub agility: f32,
+
+    // combat
+    pub structure: i32,
+    pub armour: i32,
+    pub laser_damage: i32,
+    pub laser_timer: f32,
+    pub laser_velocity: f32,
+    pub laser_type: LaserType,
+    pub time_to_reload: f32,
+    pub missile_damage: i32,
+    pub missile_timer: f32,
+    pub missile_explosion_radius: f32,
+    pub missile_velocity: f32,
+
+    pub energy: EnergyDefinition,
+    pub shield: Option<ShieldDefinition>,
+    pub special: Option<SpecialDefinition>,
+}
+
+impl ShipDefinition {
+    /// A plain ship flown in place of a definition that is missing or failed to parse.
+    /// Its sprite ids are empty, so it and its lasers are drawn with the placeholder.
+    pub fn stand_in(name: &str) -> ShipDefinition {
+        ShipDefinition {
+            name: name.to_string(),
+            sprite_sheet: String::new(),
+            laser_sprite_sheet: String::new(),
+            radius: 40.0,
+            mass: 100.0,
+            acceleration: 1.0,
+            agility: 0.05,
+            structure: 140,
+            armour: 4,
+            laser_damage: 15,
+            laser_timer: 2.5,
+            laser_velocity: 10.0,
+            laser_type: LaserType::Single,
+            time_to_reload: 0.25,
+            missile_damage: 25,
+            missile_timer: 30.0,
+            missile_explosion_radius: 6.0,
+            missile_velocity: 5.0,
+            energy: EnergyDefinition {
+                max: 40.0,
+                regen_rate: 4.0,
+                laser_cost: 2.0,
+                boost_cost: 10.0,
+                special_cost: 12.0,
+            },
+            shield: None,
+            special: None,
+        }
+    }
+
+    pub fn physical(&self) -> Physical {
+        Physical::new(self.radius, self.mass, self.acceleration, self.agility)
+    }
+
+    pub fn combat(&self) -> Combat {
+        Combat::new(
+            self.structure,
+            self.armour,
+            self.laser_damage,
+            self.laser_timer,
+            self.laser_velocity,
+            self.laser_type,
+            self.time_to_reload,
+            self.missile_damage,
+            self.missile_timer,
+            self.missile_explosion_radius,
+            self.missile_velocity,
+        )
+    }
+
+    pub fn energy(&self) -> Energy {
+        Energy::new(
+            self.energy.max,
+            self.energy.regen_rate,
+            self.energy.laser_cost,
+            self.energy.boost_cost,
+            self.energy.special_cost,
+        )
+    }
+
+    pub fn shield(&self) -> Option<Shield> {
+        self.shield.as_ref().map(|shield| Shield::new(
+            shield.max_strength,
+            shield.regen_rate,
+            shield.regen_delay,
+            shield.laser_effectiveness,
+            shield.collision_effectiveness,
+        ))
+    }
+
+    pub fn special(&self) -> Option<Special> {
+        self.special.as_ref()
+            .map(|special| Special::new(special.special_type, special.cooldown, special.duration))
+    }
+}
+
+/// ShipDefinitions holds every loaded ship definition keyed by file stem
+#[derive(Default)]
+pub struct ShipDefinitions {
+    ships: BTreeMap<String, ShipDefinition>,
+}
+
+impl ShipDefinitions {
+    /// Loads every `.ron` file in `dir`. Definitions that fail to parse are logged and skipped.
+    pub fn load(dir: &Path) -> ShipDefinitions {
+        let mut ship_definitions = ShipDefinitions::default();
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Unable to read ship directory {:?}: {}", dir, e);
+                return ship_definitions;
+            }
+        };
+
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+                continue;
+            }
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            match ShipDefinition::load(&path) {
+                Ok(definition) => {
+                    ship_definitions.ships.insert(name, definition);
+                }
+                Err(e) => log::warn!("Unable to load ship definition {:?}: {}", path, e),
+            }
+        }
+
+        ship_definitions
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ShipDefinition> {
+        self.ships.get(name)
+    }
+
+    /// The definition called `name`, or a stand-in if it is missing. The LoadingState reports which.
+    pub fn get_or_stand_in(&self, name: &str) -> ShipDefinition {
+        self.get(name).cloned().unwrap_or_else(|| ShipDefinition::stand_in(name))
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &ShipDefinition> {
+        self.ships.values()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.ships.keys().cloned().collect()
+    }
+
+    /// Adds every definition in `other`, replacing any with the same name
+    pub fn extend(&mut self, other: ShipDefinitions) {
+        self.ships.extend(other.ships);
+    }
+}
@@ -1,7 +1,7 @@
 use amethyst::{
     core::{SystemExt},
     prelude::*,    
-    core::{transform::Transform, Parent, Time},
+    core::{transform::Transform, Parent, Time, math::Vector3},
     ecs::prelude::{Entity, Join, WorldExt, Dispatcher, DispatcherBuilder},
     prelude::*,
     ui::UiText,
@@ -9,22 +9,26 @@ use amethyst::{
     renderer::Camera,
 };
 
-use crate::components::{Laser, Ship, StructureText, ScoreBoard, ScoreText};
+use crate::components::{Laser, Ship, Pilot, Hud, GravityWell,
+    Asteroid, ArenaBackground, DangerZoneBorder, Ghost, Parallax, Particle,
+    Indicator, Radar};
 use crate::systems::{CollisionSystem, MovementSystem, LaserSystem, PhysicsSystem,
-                LaserImpactAnimationSystem, WinnerSystem, AnimationControlSystem};
+                LaserImpactAnimationSystem, WinnerSystem, AnimationControlSystem,
+                InputSystem, AiPilotSystem, EnergySystem, SpecialSystem, ShieldSystem,
+                AsteroidSystem, ArenaEdgeSystem, CameraSystem, GhostSystem, ImpactSystem,
+                StarfieldSystem, ParticleSystem, HullDamageSystem, HudSystem,
+                IndicatorSystem, RadarSystem, FloatingTextSystem};
+use crate::systems::camera::{max_zoom, VIEW_WIDTH, VIEW_HEIGHT};
 
-use crate::audio::{initialize_audio};
-use crate::resources::assets::*;
+use crate::audio::{play_default_music, play_music_track};
+use crate::resources::{ArenaDefinition, AssetCache, AssetScope, CombatLog, ScreenShake};
 
-use crate::components::{initialise_ships};
-use crate::components::{initialize_scoreboard, initialize_ship_hp_ui};
+use crate::components::{initialise_ships, initialise_arena};
+use crate::components::{initialize_hud, initialize_radar};
 
 use super::pause::PauseMenuState;
 use super::utils::delete_hierarchy;
 
-pub const ARENA_HEIGHT: f32 = 1024.0 * 1.0;
-pub const ARENA_WIDTH: f32 = 1600.0 * 1.0;
-
 pub const LASER_RADIUS: f32 = 4.0;
 
 #[derive(PartialEq)]
@@ -41,6 +45,9 @@ impl Default for CurrentState {
 
 pub struct Game {
     pub player_count: u8,
+    light_pilot: Pilot,
+    dark_pilot: Pilot,
+    arena: ArenaDefinition,
     paused: bool,
     ui_root: Option<Entity>,
     fps_display: Option<Entity>,
@@ -49,52 +56,136 @@ pub struct Game {
 }
 
 impl Game {
-    pub fn new(world: &mut World) -> Self {
+    pub fn new(world: &mut World, light_pilot: Pilot, dark_pilot: Pilot, arena: ArenaDefinition) -> Self {
+
+        let player_count = vec![&light_pilot, &dark_pilot].into_iter()
+            .filter(|pilot| **pilot == Pilot::Human)
+            .count() as u8;
 
         Game {
-            player_count: 2u8,
+            player_count,
+            light_pilot,
+            dark_pilot,
+            arena,
             paused: false,
             ui_root: None,
             fps_display: None,
             text: None,
-            dispatcher: DispatcherBuilder::new()
-
-            // Add systems
-            .with(MovementSystem.pausable(CurrentState::Disabled), 
-                "movement_system", &[]
-            )
-            .with(
-                LaserSystem.pausable(CurrentState::Disabled),
-                "laser_system", &[]
-            )
-            .with(
-                PhysicsSystem.pausable(CurrentState::Disabled),
-                "physics_system", &["movement_system"]
-            )
-            .with(
-                CollisionSystem.pausable(CurrentState::Disabled),
-                "collision_system",
-                &["laser_system", "physics_system", "movement_system"],
-            )
-            .with(
-                WinnerSystem.pausable(CurrentState::Disabled),
-                "winner_system",
-                &["movement_system", "physics_system"],
-            )
-            .with(
-                LaserImpactAnimationSystem.pausable(CurrentState::Disabled),
-                "laser_impact_animation_system",
-                &["laser_system", "collision_system"],
-            )
-            .with(AnimationControlSystem.pausable(CurrentState::Disabled),
-                "animation_control_system",
-                &["laser_impact_animation_system"]
-            )
-            .build(),
+            dispatcher: build_match_dispatcher(),
         }
     }
 }
 
+/// Builds the dispatcher with every system needed to run a match.
+/// Shared by the Game and the WelcomeScreen demo.
+pub fn build_match_dispatcher() -> Dispatcher<'static, 'static> {
+    add_match_rules(DispatcherBuilder::new())
+        .with(
+            LaserImpactAnimationSystem.pausable(CurrentState::Disabled),
+            "laser_impact_animation_system",
+            &["laser_system", "collision_system"],
+        )
+        .with(AnimationControlSystem.pausable(CurrentState::Disabled),
+            "animation_control_system",
+            &["laser_impact_animation_system"]
+        )
+        .with(ImpactSystem.pausable(CurrentState::Disabled),
+            "impact_system",
+            &["arena_edge_system", "collision_system", "asteroid_system", "winner_system"]
+        )
+        .with(CameraSystem::default().pausable(CurrentState::Disabled),
+            "camera_system",
+            &["physics_system", "arena_edge_system", "collision_system", "impact_system"]
+        )
+        .with(HullDamageSystem.pausable(CurrentState::Disabled),
+            "hull_damage_system",
+            &["collision_system", "arena_edge_system", "asteroid_system", "winner_system", "special_system"]
+        )
+        .with(ParticleSystem.pausable(CurrentState::Disabled),
+            "particle_system",
+            &["movement_system", "laser_system", "collision_system", "winner_system", "hull_damage_system"]
+        )
+        .with(HudSystem.pausable(CurrentState::Disabled),
+            "hud_system",
+            &["energy_system", "laser_system", "collision_system", "arena_edge_system", "shield_system",
+                "special_system", "winner_system"]
+        )
+        .with(IndicatorSystem::default().pausable(CurrentState::Disabled),
+            "indicator_system",
+            &["camera_system", "laser_system", "collision_system"]
+        )
+        .with(RadarSystem.pausable(CurrentState::Disabled),
+            "radar_system",
+            &["camera_system", "laser_system", "collision_system", "asteroid_system"]
+        )
+        .with(FloatingTextSystem.pausable(CurrentState::Disabled),
+            "floating_text_system",
+            &["collision_system", "arena_edge_system", "winner_system", "camera_system"]
+        )
+        .with(StarfieldSystem.pausable(CurrentState::Disabled),
+            "starfield_system",
+            &["camera_system"]
+        )
+        .with(GhostSystem::default().pausable(CurrentState::Disabled),
+            "ghost_system",
+            &["movement_system", "special_system", "collision_system", "asteroid_system", "shield_system", "hull_damage_system"]
+        )
+        .build()
+}
+
+/// Adds the systems that decide how a match plays out: piloting, movement, combat and scoring.
+/// The headless batch runs these on their own, so none of them may need a window or sound to work.
+pub fn add_match_rules(builder: DispatcherBuilder<'static, 'static>) -> DispatcherBuilder<'static, 'static> {
+    builder
+        .with(InputSystem.pausable(CurrentState::Disabled),
+            "input_system", &[]
+        )
+        .with(AiPilotSystem::default().pausable(CurrentState::Disabled),
+            "ai_pilot_system", &[]
+        )
+        .with(EnergySystem.pausable(CurrentState::Disabled),
+            "energy_system", &[]
+        )
+        .with(SpecialSystem.pausable(CurrentState::Disabled),
+            "special_system", &["input_system", "ai_pilot_system", "energy_system"]
+        )
+        .with(MovementSystem.pausable(CurrentState::Disabled), 
+            "movement_system", &["input_system", "ai_pilot_system", "energy_system"]
+        )
+        .with(
+            LaserSystem.pausable(CurrentState::Disabled),
+            "laser_system", &["input_system", "ai_pilot_system", "energy_system"]
+        )
+        .with(
+            PhysicsSystem.pausable(CurrentState::Disabled),
+            "physics_system", &["movement_system"]
+        )
+        .with(
+            ArenaEdgeSystem::default().pausable(CurrentState::Disabled),
+            "arena_edge_system", &["physics_system"]
+        )
+        .with(
+            CollisionSystem.pausable(CurrentState::Disabled),
+            "collision_system",
+            &["laser_system", "physics_system", "arena_edge_system", "movement_system", "special_system"],
+        )
+        .with(
+            AsteroidSystem.pausable(CurrentState::Disabled),
+            "asteroid_system",
+            &["collision_system"],
+        )
+        .with(
+            ShieldSystem.pausable(CurrentState::Disabled),
+            "shield_system",
+            &["collision_system"],
+        )
+        .with(
+            WinnerSystem.pausable(CurrentState::Disabled),
+            "winner_system",
+            &["movement_system", "physics_system"],
+        )
+}
+
 impl SimpleState for Game {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
 
@@ -102,28 +193,20 @@ impl SimpleState for Game {
         
         self.dispatcher.setup(&mut world);
         
-        initialize_audio(world);
-
-        let _progress_counter = Some(load_assets(
-            world,
-            vec![
-                AssetType::LaserImpact,
-                AssetType::Thrust,
-                AssetType::LaserLight,
-                AssetType::LaserDark,
-                AssetType::LightShip,
-                AssetType::DarkShip,
-            ],
-        ));
-
         world.insert(RandomGen);
 
         world.register::<Parent>();
 
-        initialize_scoreboard(world);
-        initialize_ship_hp_ui(world);
+        initialize_hud(world);
+
+        match self.arena.music.as_ref() {
+            Some(music) => play_music_track(world, music),
+            None => play_default_music(world),
+        }
 
-        initialise_ships(world);
+        initialise_arena(world, self.arena.clone());
+        initialize_radar(world);
+        initialise_ships(world, self.light_pilot.clone(), self.dark_pilot.clone());
         initialise_camera(world);
 
         world.maintain();
@@ -139,7 +222,7 @@ impl SimpleState for Game {
 
     }
 
-    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
         match &event {
             StateEvent::Window(event) => {
                 if is_close_requested(&event) {
@@ -148,6 +231,11 @@ impl SimpleState for Game {
                 } else if is_key_down(&event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Push] Pausing Game!");
                     Trans::Push(Box::new(PauseMenuState::default()))
+                } else if is_key_down(&event, VirtualKeyCode::M) {
+                    if let Some(mut radar) = data.world.try_fetch_mut::<Radar>() {
+                        radar.visible = !radar.visible;
+                    }
+                    Trans::None
                 } else {
                     Trans::None
                 }
@@ -194,50 +282,205 @@ impl SimpleState for Game {
         self.fps_display = None;
         self.text = None;
 
-        // delete ships
-        let mut ships: Vec<Entity> = Vec::new();
+        delete_match_entities(data.world);
+    }
+}
 
-        for (entity, _) in (&data.world.entities(), &data.world.read_storage::<Ship>()).join() {
-            ships.push(entity);
-        }
+/// Deletes the ships, lasers, arena, HUD and camera of a match, ends any hit-stop still running
+/// and releases the assets only the arena used
+pub fn delete_match_entities(world: &mut World) {
+    // delete ships
+    let mut ships: Vec<Entity> = Vec::new();
 
-        data.world
-            .delete_entities(&ships)
-            .expect("failed to delete ships");
+    for (entity, _) in (&world.entities(), &world.read_storage::<Ship>()).join() {
+        ships.push(entity);
+    }
 
-        // delete lasers
-        let mut lasers: Vec<Entity> = Vec::new();
+    world
+        .delete_entities(&ships)
+        .expect("failed to delete ships");
 
-        for (entity, _) in (&data.world.entities(), &data.world.read_storage::<Laser>()).join() {
-            lasers.push(entity);
-        }
+    // delete lasers
+    let mut lasers: Vec<Entity> = Vec::new();
 
-        data.world
-            .delete_entities(&lasers)
-            .expect("failed to delete lasers");
+    for (entity, _) in (&world.entities(), &world.read_storage::<Laser>()).join() {
+        lasers.push(entity);
+    }
 
-        // delete ui_elements
-        let mut ui_elements: Vec<Entity> = Vec::new();
+    world
+        .delete_entities(&lasers)
+        .expect("failed to delete lasers");
 
-        for (entity, _) in (&data.world.entities(), &data.world.read_storage::<UiText>()).join() {
-            ui_elements.push(entity);
-        }
+    // delete planets
+    let mut planets: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<GravityWell>()).join() {
+        planets.push(entity);
+    }
 
-        data.world
-            .delete_entities(&ui_elements)
-            .expect("failed to delete ui_elements");
+    world
+        .delete_entities(&planets)
+        .expect("failed to delete planets");
+
+    // delete asteroids
+    let mut asteroids: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Asteroid>()).join() {
+        asteroids.push(entity);
     }
+
+    world
+        .delete_entities(&asteroids)
+        .expect("failed to delete asteroids");
+
+    // delete ghosts
+    let mut ghosts: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Ghost>()).join() {
+        ghosts.push(entity);
+    }
+
+    world
+        .delete_entities(&ghosts)
+        .expect("failed to delete ghosts");
+
+    // delete arena background
+    let mut backgrounds: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<ArenaBackground>()).join() {
+        backgrounds.push(entity);
+    }
+
+    world
+        .delete_entities(&backgrounds)
+        .expect("failed to delete arena background");
+
+    // delete particles
+    let mut particles: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Particle>()).join() {
+        particles.push(entity);
+    }
+
+    world
+        .delete_entities(&particles)
+        .expect("failed to delete particles");
+
+    // delete starfield
+    let mut stars: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Parallax>()).join() {
+        stars.push(entity);
+    }
+
+    world
+        .delete_entities(&stars)
+        .expect("failed to delete starfield");
+
+    // delete danger zone borders
+    let mut borders: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<DangerZoneBorder>()).join() {
+        borders.push(entity);
+    }
+
+    world
+        .delete_entities(&borders)
+        .expect("failed to delete danger zone borders");
+
+    // delete the hud, before the ui text sweep below catches its labels
+    let hud_elements = world.try_fetch::<Hud>()
+        .map(|hud| hud.entities());
+
+    if let Some(hud_elements) = hud_elements {
+        delete_live_entities(world, hud_elements);
+    }
+
+    // delete the radar
+    let radar_elements = world.try_fetch::<Radar>()
+        .map(|radar| radar.entities());
+
+    if let Some(radar_elements) = radar_elements {
+        delete_live_entities(world, radar_elements);
+    }
+
+    // delete off-screen indicators
+    let mut indicators: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Indicator>()).join() {
+        indicators.push(entity);
+    }
+
+    world
+        .delete_entities(&indicators)
+        .expect("failed to delete indicators");
+
+    // delete ui_elements
+    let mut ui_elements: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<UiText>()).join() {
+        ui_elements.push(entity);
+    }
+
+    world
+        .delete_entities(&ui_elements)
+        .expect("failed to delete ui_elements");
+
+    // delete cameras
+    let mut cameras: Vec<Entity> = Vec::new();
+
+    for (entity, _) in (&world.entities(), &world.read_storage::<Camera>()).join() {
+        cameras.push(entity);
+    }
+
+    world
+        .delete_entities(&cameras)
+        .expect("failed to delete cameras");
+
+    // a match can end mid hit-stop
+    if let Some(mut shake) = world.try_fetch_mut::<ScreenShake>() {
+        *shake = ScreenShake::default();
+    }
+
+    // the kill feed starts over with the next match
+    if let Some(mut combat_log) = world.try_fetch_mut::<CombatLog>() {
+        *combat_log = CombatLog::default();
+    }
+
+    // the next match may be in another arena, so its background and music are let go
+    if let Some(mut cache) = world.try_fetch_mut::<AssetCache>() {
+        cache.release(AssetScope::Arena);
+    }
+    update_time_scale(false, world);
 }
 
-/// Initialise the camera.
-fn initialise_camera(world: &mut World) {
-    // Setup camera in a way that our screen covers whole arena and (0, 0) is in the bottom light.
+/// Deletes whichever of `entities` still exist, as a resource can outlive the entities it lists
+fn delete_live_entities(world: &mut World, entities: Vec<Entity>) {
+    let live: Vec<Entity> = entities.into_iter()
+        .filter(|entity| world.entities().is_alive(*entity))
+        .collect();
+
+    world
+        .delete_entities(&live)
+        .expect("failed to delete entities");
+}
+
+/// Initialise the camera, zoomed out to show the whole arena.
+/// The CameraSystem then closes in on the ships.
+pub fn initialise_camera(world: &mut World) {
+    let (width, height, zoom) = {
+        let arena = world.read_resource::<ArenaDefinition>();
+        (arena.width, arena.height, max_zoom(&arena.state()))
+    };
+
+    // (0, 0) is in the bottom left of the arena, and the view is scaled by the camera's zoom
     let mut transform = Transform::default();
-    transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 1.0);
+    transform.set_translation_xyz(width * 0.5, height * 0.5, 1.0);
+    transform.set_scale(Vector3::new(zoom, zoom, 1.0));
 
     world
         .create_entity()
-        .with(Camera::standard_2d(ARENA_WIDTH, ARENA_HEIGHT))
+        .with(Camera::standard_2d(VIEW_WIDTH, VIEW_HEIGHT))
         .with(transform)
         .build();
 }
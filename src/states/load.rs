@@ -0,0 +1,184 @@
+use amethyst::{
+    assets::ProgressCounter,
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down},
+    prelude::*,
+    ui::{UiCreator, UiFinder, UiText, UiTransform},
+    winit::{ElementState, Event, VirtualKeyCode, WindowEvent},
+};
+
+use super::utils::delete_hierarchy;
+use crate::audio::initialize_audio;
+use crate::resources::{drop_failed_assets, load_arena_assets, load_assets, ArenaDefinition, AssetError, AssetErrors};
+
+const LABEL_LOADING: &str = "loading";
+const IMAGE_FILL: &str = "loading_fill";
+const LABEL_ERRORS: &str = "loading_errors";
+
+// width of the progress bar once full, inside its track
+const FILL_WIDTH: f32 = 400.0;
+
+/// LoadingState loads every asset a match needs that is not already cached and shows the progress on a bar,
+/// along with the background and music of the arena the match is played in, then switches to `next`. If anything fails to load the failures are listed on screen
+/// and in the log, and the player moves on with a key press once they have seen them.
+pub struct LoadingState {
+    next: Option<Box<dyn State<GameData<'static, 'static>, StateEvent>>>,
+    // arena of the match `next` starts, if it is one
+    arena: Option<ArenaDefinition>,
+    progress_counter: Option<ProgressCounter>,
+    ui_root: Option<Entity>,
+    label_loading: Option<Entity>,
+    image_fill: Option<Entity>,
+    label_errors: Option<Entity>,
+    // set once loading has finished with failures, waiting on the player
+    failed: bool,
+}
+
+impl LoadingState {
+    pub fn new(next: Box<dyn State<GameData<'static, 'static>, StateEvent>>) -> LoadingState {
+        LoadingState {
+            next: Some(next),
+            arena: None,
+            progress_counter: None,
+            ui_root: None,
+            label_loading: None,
+            image_fill: None,
+            label_errors: None,
+            failed: false,
+        }
+    }
+
+    /// Also loads the background and music of `arena`, for a `next` state that plays a match in it
+    pub fn with_arena(mut self, arena: ArenaDefinition) -> LoadingState {
+        self.arena = Some(arena);
+        self
+    }
+
+    fn proceed(&mut self) -> SimpleTrans {
+        match self.next.take() {
+            Some(next) => {
+                log::info!("[Trans::Switch] Loading finished!");
+                Trans::Switch(next)
+            }
+            None => Trans::None,
+        }
+    }
+
+    /// Lists the assets that could not be used on screen. AssetErrors has already logged them.
+    fn report_failures(&mut self, world: &mut World, errors: &[AssetError]) {
+        let mut report = String::from("FAILED TO LOAD, USING PLACEHOLDERS:\n");
+
+        for error in errors {
+            report.push_str(&format!("{}\n", error));
+        }
+        report.push_str("\nPRESS ANY KEY TO CONTINUE");
+
+        if let Some(label) = self.label_errors {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = report;
+            }
+        }
+    }
+}
+
+impl SimpleState for LoadingState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.ui_root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/loading.ron", ())));
+
+        // anything loaded on an earlier visit comes straight from the AssetCache
+        let mut progress_counter = load_assets(world);
+        initialize_audio(world, &mut progress_counter);
+        if let Some(arena) = self.arena.as_ref() {
+            load_arena_assets(world, arena, &mut progress_counter);
+        }
+        self.progress_counter = Some(progress_counter);
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let StateData { world, .. } = data;
+
+        if self.label_loading.is_none() || self.image_fill.is_none() || self.label_errors.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.label_loading = ui_finder.find(LABEL_LOADING);
+                self.image_fill = ui_finder.find(IMAGE_FILL);
+                self.label_errors = ui_finder.find(LABEL_ERRORS);
+            });
+        }
+
+        if self.failed {
+            return Trans::None;
+        }
+
+        let progress_counter = match self.progress_counter.take() {
+            Some(progress_counter) => progress_counter,
+            None => return self.proceed(),
+        };
+
+        let total = progress_counter.num_assets();
+        let done = total - progress_counter.num_loading();
+        let fraction = if total > 0 { done as f32 / total as f32 } else { 1.0 };
+
+        if let Some(fill) = self.image_fill {
+            if let Some(transform) = world.write_storage::<UiTransform>().get_mut(fill) {
+                transform.width = FILL_WIDTH * fraction;
+            }
+        }
+        if let Some(label) = self.label_loading {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = format!("LOADING {}/{}", done, total);
+            }
+        }
+
+        if !progress_counter.is_complete() {
+            self.progress_counter = Some(progress_counter);
+            return Trans::None;
+        }
+
+        world.write_resource::<AssetErrors>().report_progress(&progress_counter);
+        drop_failed_assets(world);
+
+        // each failure is only shown the first time, not on every visit
+        let errors = world.write_resource::<AssetErrors>().take_unshown();
+        if !errors.is_empty() {
+            self.report_failures(world, &errors);
+            self.failed = true;
+            return Trans::None;
+        }
+
+        self.proceed()
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if self.failed && is_any_key_down(&event) {
+                    self.proceed()
+                } else {
+                    Trans::None
+                }
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        if let Some(root) = self.ui_root.take() {
+            delete_hierarchy(root, data.world).expect("Failed to remove LoadingState");
+        }
+    }
+}
+
+/// True for any key press
+fn is_any_key_down(event: &Event) -> bool {
+    match event {
+        Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
+            input.state == ElementState::Pressed
+        }
+        _ => false,
+    }
+}
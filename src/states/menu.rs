@@ -2,25 +2,35 @@ use amethyst::{
     ecs::prelude::Entity,
     input::{is_close_requested, is_key_down},
     prelude::*,
-    ui::{UiCreator, UiEvent, UiEventType, UiFinder},
+    ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText},
+    utils::application_root_dir,
     winit::VirtualKeyCode,
+    config::Config,
 };
 
 use super::{
     credits::CreditsScreen,
     game::Game,
+    load::LoadingState,
+    mods::ModsScreen,
     utils::delete_hierarchy,
     welcome::WelcomeScreen,
 };
 
 use log::{info, warn};
 
-use crate::audio::initialize_audio;
+use crate::audio::play_default_music;
+use crate::components::Pilot;
+use crate::resources::{AiScripts, ArenaDefinition, Arenas, DEFAULT_ARENA, FeedbackSettings, FEEDBACK_CONFIG, ModPacks};
 
 const BUTTON_2P_START: &str = "2p_start";
 const BUTTON_1P_START: &str = "1p_start";
 const BUTTON_OPTIONS: &str = "options";
 const BUTTON_CREDITS: &str = "credits";
+const LABEL_OPPONENT: &str = "opponent";
+const LABEL_ARENA: &str = "arena";
+const LABEL_EFFECTS: &str = "effects";
+const LABEL_MODS: &str = "mods";
 
 #[derive(Default, Debug)]
 pub struct MainMenu {
@@ -29,6 +39,111 @@ pub struct MainMenu {
     button_1p_start: Option<Entity>,
     button_options: Option<Entity>,
     button_credits: Option<Entity>,
+    label_opponent: Option<Entity>,
+    label_arena: Option<Entity>,
+    label_effects: Option<Entity>,
+    label_mods: Option<Entity>,
+    // index into the loaded AI scripts for the 1P opponent
+    opponent: usize,
+    // index into the loaded arenas for the next match
+    arena: usize,
+}
+
+impl MainMenu {
+    /// Name of the AI script currently selected as the 1P opponent
+    fn opponent_script(&self, world: &World) -> Option<String> {
+        let names = world.read_resource::<AiScripts>().names();
+        if names.is_empty() {
+            None
+        } else {
+            Some(names[self.opponent % names.len()].clone())
+        }
+    }
+
+    fn update_opponent_label(&self, world: &mut World) {
+        let text = match self.opponent_script(world) {
+            Some(name) => format!("VS: {} (TAB)", name.to_uppercase()),
+            None => "VS: NO AI SCRIPTS".to_string(),
+        };
+
+        if let Some(label) = self.label_opponent {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    /// Arena currently selected for the next match
+    fn selected_arena(&self, world: &World) -> ArenaDefinition {
+        let arenas = world.read_resource::<Arenas>();
+        let names = arenas.names();
+        if names.is_empty() {
+            ArenaDefinition::default()
+        } else {
+            arenas.get(&names[self.arena % names.len()]).unwrap().clone()
+        }
+    }
+
+    fn update_arena_label(&self, world: &mut World) {
+        let arena = self.selected_arena(world);
+        let text = format!("< ARENA: {} >", arena.name.to_uppercase());
+
+        if let Some(label) = self.label_arena {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    fn update_effects_label(&self, world: &mut World) {
+        let settings = *world.read_resource::<FeedbackSettings>();
+        let text = if settings.any_enabled() {
+            "SCREEN SHAKE: ON"
+        } else {
+            "SCREEN SHAKE: OFF"
+        };
+
+        if let Some(label) = self.label_effects {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = text.to_string();
+            }
+        }
+    }
+
+    fn update_mods_label(&self, world: &mut World) {
+        let text = {
+            let mod_packs = world.read_resource::<ModPacks>();
+            format!("MODS: {} OF {} ON (M)", mod_packs.enabled().count(), mod_packs.packs.len())
+        };
+
+        if let Some(label) = self.label_mods {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    /// Turns screen shake and hit-stop both off, or both back on, and saves the choice
+    fn toggle_effects(&self, world: &mut World) {
+        let settings = {
+            let mut settings = world.write_resource::<FeedbackSettings>();
+            let enabled = !settings.any_enabled();
+            settings.screen_shake = enabled;
+            settings.hit_stop = enabled;
+            *settings
+        };
+
+        match application_root_dir() {
+            Ok(app_root) => {
+                if let Err(e) = settings.write(app_root.join("config").join(FEEDBACK_CONFIG)) {
+                    warn!("Unable to save feedback settings: {}", e);
+                }
+            }
+            Err(e) => warn!("Unable to find config directory: {}", e),
+        }
+
+        self.update_effects_label(world);
+    }
 }
 
 impl SimpleState for MainMenu {
@@ -40,8 +155,11 @@ impl SimpleState for MainMenu {
             Some(world.exec(|mut creator: UiCreator<'_>|
                 creator.create("ui/menu.ron", ())));
 
-        initialize_audio(world);
+        play_default_music(world);
 
+        self.arena = world.read_resource::<Arenas>().names().iter()
+            .position(|name| name == DEFAULT_ARENA)
+            .unwrap_or(0);
     }
 
     fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
@@ -52,13 +170,25 @@ impl SimpleState for MainMenu {
             || self.button_1p_start.is_none()
             || self.button_options.is_none()
             || self.button_credits.is_none()
+            || self.label_opponent.is_none()
+            || self.label_arena.is_none()
+            || self.label_effects.is_none()
+            || self.label_mods.is_none()
         {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.button_2p_start = ui_finder.find(BUTTON_2P_START);
                 self.button_1p_start = ui_finder.find(BUTTON_1P_START);
                 self.button_options = ui_finder.find(BUTTON_OPTIONS);
                 self.button_credits = ui_finder.find(BUTTON_CREDITS);
+                self.label_opponent = ui_finder.find(LABEL_OPPONENT);
+                self.label_arena = ui_finder.find(LABEL_ARENA);
+                self.label_effects = ui_finder.find(LABEL_EFFECTS);
+                self.label_mods = ui_finder.find(LABEL_MODS);
             });
+            self.update_opponent_label(world);
+            self.update_arena_label(world);
+            self.update_effects_label(world);
+            self.update_mods_label(world);
         }
         Trans::None
     }
@@ -76,6 +206,22 @@ impl SimpleState for MainMenu {
                 } else if is_key_down(&event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Switch] Switching back to WelcomeScreen!");
                     Trans::Switch(Box::new(WelcomeScreen::default()))
+                } else if is_key_down(&event, VirtualKeyCode::M) {
+                    log::info!("[Trans::Switch] Switching to ModsScreen!");
+                    Trans::Switch(Box::new(ModsScreen::default()))
+                } else if is_key_down(&event, VirtualKeyCode::Tab) {
+                    self.opponent += 1;
+                    self.update_opponent_label(data.world);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Right) {
+                    self.arena += 1;
+                    self.update_arena_label(data.world);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Left) {
+                    let count = data.world.read_resource::<Arenas>().names().len().max(1);
+                    self.arena = (self.arena + count - 1) % count;
+                    self.update_arena_label(data.world);
+                    Trans::None
                 } else {
                     Trans::None
                 }
@@ -88,12 +234,25 @@ impl SimpleState for MainMenu {
                     log::info!("[Trans::Switch] Switching to CreditsScreen!");
                     return Trans::Switch(Box::new(CreditsScreen::default()));
                 }
+                let arena = self.selected_arena(data.world);
                 if Some(target) == self.button_2p_start {
-                    log::info!("[Trans::Switch] Switching to Game!");
-                    return Trans::Switch(Box::new(Game::new(data.world)));
+                    log::info!("[Trans::Switch] Switching to Game in {}!", arena.name);
+                    return Trans::Switch(Box::new(LoadingState::new(Box::new(Game::new(data.world, Pilot::Human, Pilot::Human, arena.clone()))).with_arena(arena)));
+                }
+                if Some(target) == self.button_1p_start {
+                    return match self.opponent_script(data.world) {
+                        Some(script) => {
+                            log::info!("[Trans::Switch] Switching to Game against {} in {}!", script, arena.name);
+                            Trans::Switch(Box::new(LoadingState::new(Box::new(Game::new(data.world, Pilot::Human, Pilot::Script(script), arena.clone()))).with_arena(arena)))
+                        }
+                        None => {
+                            log::warn!("No AI scripts loaded, unable to start 1P game!");
+                            Trans::None
+                        }
+                    };
                 }
-                if Some(target) == self.button_1p_start || Some(target) == self.button_options {
-                    log::info!("This Button's functionality is not yet implemented!");
+                if Some(target) == self.button_options {
+                    self.toggle_effects(data.world);
                 }
                 Trans::None
             }
@@ -111,5 +270,9 @@ impl SimpleState for MainMenu {
         self.button_1p_start = None;
         self.button_options = None;
         self.button_credits = None;
+        self.label_opponent = None;
+        self.label_arena = None;
+        self.label_effects = None;
+        self.label_mods = None;
     }
 }
\ No newline at end of file
@@ -4,9 +4,13 @@ pub mod credits;
 pub mod utils;
 pub mod welcome;
 pub mod menu;
+pub mod load;
+pub mod mods;
 
-pub use self::game::{ARENA_HEIGHT, ARENA_WIDTH, Game, LASER_RADIUS, RandomGen, CurrentState};
+pub use self::game::{Game, LASER_RADIUS, RandomGen, CurrentState, add_match_rules};
 pub use self::pause::PauseMenuState;
 pub use self::utils::delete_hierarchy;
 pub use self::welcome::WelcomeScreen;
-pub use self::menu::*;
\ No newline at end of file
+pub use self::menu::*;
+pub use self::load::LoadingState;
+pub use self::mods::ModsScreen;
\ No newline at end of file
@@ -0,0 +1,160 @@
+use amethyst::{
+    config::Config,
+    ecs::prelude::Entity,
+    input::{is_close_requested, is_key_down},
+    prelude::*,
+    ui::{UiCreator, UiFinder, UiText},
+    utils::application_root_dir,
+    winit::VirtualKeyCode,
+};
+
+use log::warn;
+
+use super::menu::MainMenu;
+use super::utils::delete_hierarchy;
+use crate::resources::{ModPacks, MODS_CONFIG};
+
+const LABEL_MOD_LIST: &str = "mod_list";
+const LABEL_MOD_STATUS: &str = "mod_status";
+
+/// ModsScreen lists the content packs in the mods directory in load order and turns them on and off.
+/// The choice is saved straight away, but only takes effect the next time the game starts.
+#[derive(Debug, Default)]
+pub struct ModsScreen {
+    ui_root: Option<Entity>,
+    label_mod_list: Option<Entity>,
+    label_mod_status: Option<Entity>,
+    // index into ModPacks::packs of the highlighted pack
+    selected: usize,
+}
+
+impl ModsScreen {
+    fn update_labels(&self, world: &mut World) {
+        let (list, status) = {
+            let mod_packs = world.read_resource::<ModPacks>();
+
+            let list = if mod_packs.packs.is_empty() {
+                "NO CONTENT PACKS FOUND IN THE MODS DIRECTORY".to_string()
+            } else {
+                mod_packs.packs.iter().enumerate()
+                    .map(|(index, pack)| format!(
+                        "{} [{}] {} {} - {}\n",
+                        if index == self.selected { ">" } else { " " },
+                        if pack.enabled { "ON" } else { "OFF" },
+                        pack.manifest.name.to_uppercase(),
+                        pack.manifest.version,
+                        pack.manifest.description,
+                    ))
+                    .collect()
+            };
+
+            let mut status = String::new();
+            if !mod_packs.conflicts.is_empty() {
+                status.push_str(&format!("{} CONFLICTS BETWEEN ENABLED PACKS, SEE THE LOG\n", mod_packs.conflicts.len()));
+            }
+            if mod_packs.changed {
+                status.push_str("RESTART THE GAME TO APPLY CHANGES");
+            }
+
+            (list, status)
+        };
+
+        let mut ui_texts = world.write_storage::<UiText>();
+        if let Some(label) = self.label_mod_list {
+            if let Some(ui_text) = ui_texts.get_mut(label) {
+                ui_text.text = list;
+            }
+        }
+        if let Some(label) = self.label_mod_status {
+            if let Some(ui_text) = ui_texts.get_mut(label) {
+                ui_text.text = status;
+            }
+        }
+    }
+
+    /// Turns the highlighted pack on or off and saves the choice
+    fn toggle_selected(&self, world: &mut World) {
+        let settings = {
+            let mut mod_packs = world.write_resource::<ModPacks>();
+            mod_packs.toggle(self.selected);
+            mod_packs.settings().clone()
+        };
+
+        match application_root_dir() {
+            Ok(app_root) => {
+                if let Err(e) = settings.write(app_root.join("config").join(MODS_CONFIG)) {
+                    warn!("Unable to save mod settings: {}", e);
+                }
+            }
+            Err(e) => warn!("Unable to find config directory: {}", e),
+        }
+
+        self.update_labels(world);
+    }
+}
+
+impl SimpleState for ModsScreen {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.ui_root =
+            Some(world.exec(|mut creator: UiCreator<'_>|
+                creator.create("ui/mods.ron", ())));
+    }
+
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let StateData { world, .. } = state_data;
+
+        if self.label_mod_list.is_none() || self.label_mod_status.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.label_mod_list = ui_finder.find(LABEL_MOD_LIST);
+                self.label_mod_status = ui_finder.find(LABEL_MOD_STATUS);
+            });
+            self.update_labels(world);
+        }
+        Trans::None
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) => {
+                let count = data.world.read_resource::<ModPacks>().packs.len().max(1);
+
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else if is_key_down(&event, VirtualKeyCode::Down) {
+                    self.selected = (self.selected + 1) % count;
+                    self.update_labels(data.world);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Up) {
+                    self.selected = (self.selected + count - 1) % count;
+                    self.update_labels(data.world);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Return) || is_key_down(&event, VirtualKeyCode::Space) {
+                    self.toggle_selected(data.world);
+                    Trans::None
+                } else {
+                    Trans::None
+                }
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        if let Some(entity) = self.ui_root {
+            delete_hierarchy(entity, data.world).expect("Failed to remove ModsScreen");
+        }
+        self.ui_root = None;
+        self.label_mod_list = None;
+        self.label_mod_status = None;
+    }
+}
\ No newline at end of file
@@ -1,48 +1,134 @@
 use amethyst::{
-    ecs::prelude::Entity,
-    core::transform::Parent,
+    ecs::prelude::{Dispatcher, Entity},
+    core::{transform::Parent, Hidden, Time},
     input::{is_close_requested, is_key_down, is_mouse_button_down},
     prelude::*,
-    ui::UiCreator,
-    winit::{MouseButton, VirtualKeyCode},
+    ui::{UiCreator, UiFinder},
+    winit::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent},
 };
 
 use super::utils::delete_hierarchy;
-use crate::resources::{AssetType, load_assets};
-use super::game::RandomGen;
-use crate::audio::initialize_audio;
+use crate::resources::{AiScripts, ArenaDefinition, Arenas};
+use crate::components::{Pilot, initialise_ships, initialize_hud, initialize_radar, initialise_arena};
+use super::game::{RandomGen, build_match_dispatcher, delete_match_entities, initialise_camera};
+use crate::audio::play_default_music;
 
-#[derive(Default, Debug)]
+/// Seconds without input before the AI demo starts
+const DEMO_IDLE_TIME: f32 = 10.0;
+
+const IMAGE_WELCOME: &str = "welcome_image";
+
+#[derive(Default)]
 pub struct WelcomeScreen {
     ui_handle: Option<Entity>,
+    // full window image, which would cover the demo
+    welcome_image: Option<Entity>,
+    idle_timer: f32,
+    // runs an AI vs AI match behind the welcome UI while idle
+    demo: Option<Dispatcher<'static, 'static>>,
+}
+
+impl WelcomeScreen {
+    /// Hides the welcome image so the demo can be seen through it, or shows it again
+    fn set_image_hidden(&mut self, world: &mut World, hidden: bool) {
+        if self.welcome_image.is_none() {
+            self.welcome_image = world.exec(|ui_finder: UiFinder<'_>| ui_finder.find(IMAGE_WELCOME));
+        }
+
+        if let Some(image) = self.welcome_image {
+            let mut hidden_storage = world.write_storage::<Hidden>();
+            let result = if hidden {
+                hidden_storage.insert(image, Hidden).map(|_| ())
+            } else {
+                hidden_storage.remove(image);
+                Ok(())
+            };
+
+            if let Err(e) = result {
+                log::warn!("Unable to hide the welcome image: {}", e);
+            }
+        }
+    }
+
+    /// Starts an AI vs AI match using two of the loaded AI scripts in a random arena
+    fn start_demo(&mut self, world: &mut World) {
+        let names = world.read_resource::<AiScripts>().names();
+        if names.is_empty() {
+            log::warn!("No AI scripts loaded, skipping demo mode");
+            return;
+        }
+
+        let (light, dark, arena) = {
+            let random_gen = world.read_resource::<RandomGen>();
+            let arenas = world.read_resource::<Arenas>();
+            let arena_names = arenas.names();
+            let arena = if arena_names.is_empty() {
+                ArenaDefinition::default()
+            } else {
+                let name = &arena_names[random_gen.next_usize() % arena_names.len()];
+                arenas.get(name).unwrap().clone()
+            };
+            (
+                names[random_gen.next_usize() % names.len()].clone(),
+                names[random_gen.next_usize() % names.len()].clone(),
+                arena,
+            )
+        };
+        log::info!("Starting demo: {} vs {} in {}", light, dark, arena.name);
+
+        let mut dispatcher = build_match_dispatcher();
+        dispatcher.setup(world);
+
+        initialize_hud(world);
+
+        initialise_arena(world, arena);
+        initialize_radar(world);
+        initialise_ships(world, Pilot::Script(light), Pilot::Script(dark));
+        initialise_camera(world);
+
+        world.maintain();
+
+        self.set_image_hidden(world, true);
+        self.demo = Some(dispatcher);
+    }
+
+    fn stop_demo(&mut self, world: &mut World) {
+        if self.demo.take().is_some() {
+            delete_match_entities(world);
+            self.set_image_hidden(world, false);
+        }
+        self.idle_timer = 0.0;
+    }
 }
 
 impl SimpleState for WelcomeScreen {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
-        initialize_audio(world);
+        play_default_music(world);
 
         world.insert(RandomGen);
 
-        let _progress_counter = Some(load_assets(
-            world,
-            vec![
-                AssetType::LaserImpact,
-                AssetType::Thrust,
-                AssetType::LaserLight,
-                AssetType::LaserDark,
-                AssetType::LightShip,
-                AssetType::DarkShip,
-            ],
-        ));
-
         world.register::<Parent>();
 
         self.ui_handle =
             Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/welcome.ron", ())));
     }
 
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if let Some(dispatcher) = self.demo.as_mut() {
+            dispatcher.dispatch(&data.world);
+        } else {
+            self.idle_timer += data.world.read_resource::<Time>().delta_seconds();
+
+            if self.idle_timer >= DEMO_IDLE_TIME {
+                self.start_demo(data.world);
+            }
+        }
+
+        Trans::None
+    }
+
     fn handle_event(
         &mut self,
         _: StateData<'_, GameData<'_, '_>>,
@@ -50,13 +136,23 @@ impl SimpleState for WelcomeScreen {
     ) -> SimpleTrans {
         match &event {
             StateEvent::Window(event) => {
-                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if self.demo.is_some() && is_any_input(&event) {
+                    // any input during the demo returns to the menu
+                    log::info!("[Trans::Switch] Leaving demo, switching to MainMenu!");
+                    Trans::Switch(Box::new(super::menu::MainMenu::default()))
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Quit] Quitting Application!");
                     Trans::Quit
                 } else if is_mouse_button_down(&event, MouseButton::Left) {
                     log::info!("[Trans::Switch] Switching to MainMenu!");
                     Trans::Switch(Box::new(super::menu::MainMenu::default()))
                 } else {
+                    if is_any_input(&event) {
+                        self.idle_timer = 0.0;
+                    }
                     Trans::None
                 }
             }
@@ -65,9 +161,24 @@ impl SimpleState for WelcomeScreen {
     }
 
     fn on_stop(&mut self, data: StateData<GameData>) {
+        self.stop_demo(data.world);
+
         if let Some(handler) = self.ui_handle {
             delete_hierarchy(handler, data.world).expect("Failed to remove WelcomeScreen");
         }
         self.ui_handle = None;
+        self.welcome_image = None;
     }
-}
\ No newline at end of file
+}
+
+/// True for any key press or mouse button press
+fn is_any_input(event: &Event) -> bool {
+    match event {
+        Event::WindowEvent { event, .. } => match event {
+            WindowEvent::KeyboardInput { input, .. } => input.state == ElementState::Pressed,
+            WindowEvent::MouseInput { state, .. } => *state == ElementState::Pressed,
+            _ => false,
+        },
+        _ => false,
+    }
+}
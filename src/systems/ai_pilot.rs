@@ -0,0 +1,136 @@
+use amethyst::{
+    core::{math::Vector3, Hidden, SystemDesc, Transform},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::components::{Combat, DangerZone, Energy, Laser, Physical, Pilot, Shield, Ship, ShipControl};
+use crate::resources::{AiScripts, ArenaDefinition, ArenaState, ProjectileState, ShipState};
+
+/// Runs the AI script of every script piloted ship and writes its ShipControl
+#[derive(SystemDesc, Default)]
+pub struct AiPilotSystem {
+    // scripts that have already reported an error, so the log is not flooded every frame
+    failed: HashSet<String>,
+    // where each ship was last seen, handed to its enemy's pilot while it is cloaked
+    last_seen: HashMap<Entity, ShipState>,
+}
+
+impl<'s> System<'s> for AiPilotSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Pilot>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, Combat>,
+        ReadStorage<'s, Energy>,
+        ReadStorage<'s, Shield>,
+        ReadStorage<'s, Hidden>,
+        WriteStorage<'s, ShipControl>,
+        ReadExpect<'s, AiScripts>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, DangerZone>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        pilots,
+        lasers,
+        transforms,
+        physicals,
+        combats,
+        energies,
+        shields,
+        hidden,
+        mut controls,
+        ai_scripts,
+        arena,
+        zone,
+    ): Self::SystemData) {
+
+        let arena = ArenaState { margin: zone.margin, ..arena.state() };
+
+        let states: Vec<(Entity, &Ship, ShipState)> = (&entities, &ships, &transforms, &physicals, &combats, &energies, shields.maybe())
+            .join()
+            .map(|(entity, ship, transform, physical, combat, energy, shield)| {
+                (entity, ship, ship_state(transform, physical, combat, energy, shield))
+            })
+            .collect();
+
+        for (entity, _, state) in states.iter() {
+            if !hidden.contains(*entity) {
+                self.last_seen.insert(*entity, *state);
+            }
+        }
+
+        for (entity, ship, me) in states.iter() {
+            let script = match pilots.get(*entity) {
+                Some(Pilot::Script(script)) => script,
+                _ => continue,
+            };
+
+            let enemy = match states.iter().find(|(_, other, _)| other.side != ship.side) {
+                Some((enemy_entity, _, enemy)) if hidden.contains(*enemy_entity) => {
+                    // a cloaked enemy is only known by where it was when it vanished
+                    self.last_seen.get(enemy_entity).unwrap_or(enemy)
+                }
+                Some((_, _, enemy)) => enemy,
+                None => continue,
+            };
+
+            let projectiles: Vec<ProjectileState> = (&lasers, &transforms, &physicals)
+                .join()
+                .map(|(laser, transform, physical)| ProjectileState {
+                    x: transform.translation().x,
+                    y: transform.translation().y,
+                    vx: physical.velocity[0],
+                    vy: physical.velocity[1],
+                    hostile: laser.side != ship.side,
+                })
+                .collect();
+
+            let control = match ai_scripts.think(script, me, enemy, &projectiles, &arena) {
+                Ok(control) => control,
+                Err(e) => {
+                    if self.failed.insert(script.clone()) {
+                        log::error!("AI script {} failed: {}", script, e);
+                    }
+                    ShipControl::default()
+                }
+            };
+
+            if let Some(ship_control) = controls.get_mut(*entity) {
+                *ship_control = control;
+            }
+        }
+    }
+}
+
+/// Builds the script view of a ship from its components
+pub fn ship_state(
+    transform: &Transform,
+    physical: &Physical,
+    combat: &Combat,
+    energy: &Energy,
+    shield: Option<&Shield>,
+) -> ShipState {
+    let forward = transform.rotation() * Vector3::y();
+
+    ShipState {
+        x: transform.translation().x,
+        y: transform.translation().y,
+        vx: physical.velocity[0],
+        vy: physical.velocity[1],
+        heading: forward.y.atan2(forward.x),
+        radius: physical.radius,
+        structure: combat.structure,
+        energy: energy.current,
+        max_energy: energy.max,
+        shield: shield.map_or(0.0, |shield| shield.strength),
+    }
+}
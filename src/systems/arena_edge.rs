@@ -0,0 +1,203 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, Source},
+    core::{Transform, SystemDesc, Time, math::{Vector2, Vector3}},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, SystemData, World, Write, WriteStorage},
+};
+
+use std::ops::Deref;
+
+use crate::audio::{play_impact_sound, Sounds};
+use crate::components::{Combat, DamageReport, DamageType, DangerZone, DangerZoneBorder, Edge, Physical, Shield, Ship, Special};
+use crate::resources::{ArenaDefinition, CombatLog, EdgeMode, ScreenShake, Weapon};
+
+// danger zone damage is dealt in whole points every tick
+const ZONE_TICK: f32 = 0.5;
+// pixels per side of pixel.png
+const PIXEL_SIZE: f32 = 4.0;
+
+/// Keeps everything inside arenas with walls, damaging ships that hit them,
+/// and closes the danger zone in on ships caught outside it
+#[derive(SystemDesc, Default)]
+pub struct ArenaEdgeSystem {
+    tick_timer: f32,
+}
+
+impl<'s> System<'s> for ArenaEdgeSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Special>,
+        ReadStorage<'s, DangerZoneBorder>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Physical>,
+        WriteStorage<'s, Combat>,
+        WriteStorage<'s, Shield>,
+        Read<'s, ArenaDefinition>,
+        Write<'s, DangerZone>,
+        Write<'s, ScreenShake>,
+        Write<'s, CombatLog>,
+        Read<'s, Time>,
+
+        Read<'s, AssetStorage<Source>>,
+        ReadExpect<'s, Sounds>,
+        Option<Read<'s, Output>>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        specials,
+        borders,
+        mut transforms,
+        mut physicals,
+        mut combats,
+        mut shields,
+        arena,
+        mut zone,
+        mut shake,
+        mut combat_log,
+        time,
+
+        storage,
+        sounds,
+        audio_output,
+    ): Self::SystemData) {
+
+        let wall_damage = match arena.edge_mode {
+            EdgeMode::Wrap => return,
+            EdgeMode::Bounce { damage } => damage,
+            EdgeMode::DangerZone { .. } => 0,
+        };
+
+        // walls
+        let mut ships_hit: Vec<Entity> = Vec::new();
+
+        for (entity, transform, physical) in (&entities, &mut transforms, &mut physicals).join() {
+            let mut x = transform.translation().x;
+            let mut y = transform.translation().y;
+
+            let hit = bounce_off_walls(&mut x, &mut y, &mut physical.velocity, physical.radius, arena.width, arena.height);
+
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
+
+            if hit && ships.contains(entity) {
+                ships_hit.push(entity);
+            }
+        }
+
+        if wall_damage > 0 {
+            for entity in ships_hit {
+                if specials.get(entity).map_or(false, |special| special.is_shielding()) {
+                    continue;
+                }
+
+                if let (Some(combat), Some(transform)) = (combats.get_mut(entity), transforms.get(entity)) {
+                    let report = combat.take_damage(shields.get_mut(entity), wall_damage, DamageType::Collision);
+                    combat_log.hit(
+                        entity,
+                        None,
+                        transform.translation().x,
+                        transform.translation().y,
+                        report,
+                        Weapon::Wall,
+                    );
+                    shake.impact(wall_damage as f32);
+                    play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+                }
+            }
+        }
+
+        // danger zone
+        if let EdgeMode::DangerZone { delay, shrink_rate, max_margin, damage_per_second } = arena.edge_mode {
+            zone.elapsed += time.delta_seconds();
+            zone.margin = zone_margin(zone.elapsed, delay, shrink_rate, max_margin, arena.width, arena.height);
+
+            self.tick_timer -= time.delta_seconds();
+            if self.tick_timer <= 0.0 {
+                self.tick_timer = ZONE_TICK;
+
+                // the zone burns straight through shields and armour
+                let damage = (damage_per_second * ZONE_TICK).round() as i32;
+
+                for (entity, _, transform, combat) in (&entities, &ships, &transforms, &mut combats).join() {
+                    if outside_zone(transform.translation().x, transform.translation().y, zone.margin, arena.width, arena.height) {
+                        combat.structure -= damage;
+
+                        let report = DamageReport { raw: damage, dealt: damage, ..DamageReport::default() };
+                        combat_log.hit(
+                            entity,
+                            None,
+                            transform.translation().x,
+                            transform.translation().y,
+                            report,
+                            Weapon::DangerZone,
+                        );
+                    }
+                }
+            }
+
+            for (border, transform) in (&borders, &mut transforms).join() {
+                size_border(border.edge, transform, zone.margin, arena.width, arena.height);
+            }
+        }
+    }
+}
+
+/// Moves a body that has crossed a wall back inside, reflecting it if it was heading out.
+/// Returns true if it bounced.
+fn bounce_off_walls(x: &mut f32, y: &mut f32, velocity: &mut Vector2<f32>, radius: f32, width: f32, height: f32) -> bool {
+    let hit_x = bounce_axis(x, &mut velocity[0], radius, width);
+    let hit_y = bounce_axis(y, &mut velocity[1], radius, height);
+
+    hit_x || hit_y
+}
+
+fn bounce_axis(position: &mut f32, speed: &mut f32, radius: f32, size: f32) -> bool {
+    if *position < radius {
+        *position = radius;
+        if *speed < 0.0 {
+            *speed = -*speed;
+            return true;
+        }
+    } else if *position > size - radius {
+        *position = size - radius;
+        if *speed > 0.0 {
+            *speed = -*speed;
+            return true;
+        }
+    }
+
+    false
+}
+
+/// How far the danger zone has closed in from each edge after `elapsed` seconds.
+/// Never closes past a small square in the middle of the arena.
+fn zone_margin(elapsed: f32, delay: f32, shrink_rate: f32, max_margin: f32, width: f32, height: f32) -> f32 {
+    let limit = (width.min(height) / 2.0 - 100.0).max(0.0);
+
+    ((elapsed - delay) * shrink_rate)
+        .max(0.0)
+        .min(max_margin)
+        .min(limit)
+}
+
+fn outside_zone(x: f32, y: f32, margin: f32, width: f32, height: f32) -> bool {
+    x < margin || x > width - margin || y < margin || y > height - margin
+}
+
+fn size_border(edge: Edge, transform: &mut Transform, margin: f32, width: f32, height: f32) {
+    // left and right strips run the full height, top and bottom fill the gap between them
+    let (x, y, w, h) = match edge {
+        Edge::Left => (margin / 2.0, height / 2.0, margin, height),
+        Edge::Right => (width - margin / 2.0, height / 2.0, margin, height),
+        Edge::Bottom => (width / 2.0, margin / 2.0, width - margin * 2.0, margin),
+        Edge::Top => (width / 2.0, height - margin / 2.0, width - margin * 2.0, margin),
+    };
+
+    transform.set_translation_x(x);
+    transform.set_translation_y(y);
+    transform.set_scale(Vector3::new(w / PIXEL_SIZE, h / PIXEL_SIZE, 1.0));
+}
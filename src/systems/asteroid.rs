@@ -0,0 +1,114 @@
+use amethyst::{
+    core::{Transform, SystemDesc, Time, math::Vector2},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, World, Write, WriteStorage},
+};
+
+use crate::components::{Asteroid, GravityWell, Physical, Ship, MIN_SPLIT_RADIUS, random_position, random_spin, spawn_asteroid};
+use crate::resources::{ArenaDefinition, SPRITE_ASTEROID, PREFAB_LASER_IMPACT, PrefabList, ScreenShake, SpriteSheetList};
+use crate::states::RandomGen;
+use crate::systems::laser::show_laser_impact;
+
+// extra speed given to fragments, pushing them apart
+const FRAGMENT_SPEED: f32 = 0.75;
+
+/// Spins asteroids, splits destroyed ones into fragments and tops the field back up
+#[derive(SystemDesc)]
+pub struct AsteroidSystem;
+
+impl<'s> System<'s> for AsteroidSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Asteroid>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, GravityWell>,
+        ReadStorage<'s, Ship>,
+        WriteStorage<'s, Transform>,
+        Read<'s, ArenaDefinition>,
+        Write<'s, ScreenShake>,
+        Read<'s, SpriteSheetList>,
+        ReadExpect<'s, PrefabList>,
+        ReadExpect<'s, RandomGen>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (entities, asteroids, physicals, gravity_wells, ships, mut transforms, arena, mut shake, sprite_sheet_list, prefab_list, random_gen, lazy_update, time): Self::SystemData) {
+        let sprite_sheet = sprite_sheet_list.get(SPRITE_ASTEROID).cloned();
+
+        let mut remaining = 0;
+
+        for (entity, asteroid, physical, transform) in (&entities, &asteroids, &physicals, &mut transforms).join() {
+            if asteroid.structure > 0 {
+                transform.rotate_2d(asteroid.spin * time.delta_seconds());
+                remaining += 1;
+                continue;
+            }
+
+            let x = transform.translation().x;
+            let y = transform.translation().y;
+            let radius = physical.radius / 2.0;
+
+            if radius >= MIN_SPLIT_RADIUS {
+                // split in two, flying apart across the line of travel
+                let across = if physical.velocity.magnitude() > 0.0 {
+                    Vector2::new(-physical.velocity[1], physical.velocity[0]).normalize()
+                } else {
+                    Vector2::new(1.0, 0.0)
+                };
+
+                for side in [-1.0, 1.0].iter() {
+                    spawn_asteroid(
+                        &entities,
+                        &lazy_update,
+                        sprite_sheet.clone(),
+                        x + across[0] * radius * side,
+                        y + across[1] * radius * side,
+                        radius,
+                        physical.velocity + across * (FRAGMENT_SPEED * side),
+                        random_spin(&random_gen),
+                    );
+                }
+                remaining += 2;
+            }
+
+            // bigger rocks break up with a bigger bang
+            shake.impact(physical.radius / 2.0);
+
+            // debris burst where the asteroid broke up
+            if let Some(prefab_handle) = prefab_list.get(PREFAB_LASER_IMPACT) {
+                show_laser_impact(&entities, prefab_handle.clone(), transform.clone(), &lazy_update);
+            }
+
+            entities.delete(entity).expect("Unable to delete asteroid");
+        }
+
+        // a new asteroid joins the field while it is thin, placed the way the field was seeded
+        if let Some(field) = arena.asteroids.as_ref() {
+            if remaining < field.min_count {
+                let radius = field.random_radius(&random_gen);
+
+                // it must not land on a planet, or on a ship mid-round
+                let wells = (&transforms, &gravity_wells).join()
+                    .map(|(transform, well)| (transform.translation().x, transform.translation().y, well.radius));
+                let ship_bodies = (&transforms, &physicals, &ships).join()
+                    .map(|(transform, physical, _)| (transform.translation().x, transform.translation().y, physical.radius));
+                let keep_clear: Vec<(f32, f32, f32)> = wells.chain(ship_bodies).collect();
+
+                // with no clear spot this frame, it is tried again on the next
+                if let Some((x, y)) = random_position(&random_gen, arena.width, arena.height, radius, &keep_clear) {
+                    spawn_asteroid(
+                        &entities,
+                        &lazy_update,
+                        sprite_sheet,
+                        x,
+                        y,
+                        radius,
+                        field.random_velocity(&random_gen),
+                        random_spin(&random_gen),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use amethyst::{
+    core::{Transform, SystemDesc, Time, math::Vector3},
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::Camera,
+};
+
+use crate::components::Ship;
+use crate::resources::{ArenaDefinition, ArenaState, FeedbackSettings, ScreenShake};
+use crate::states::RandomGen;
+
+/// Size of the camera's view at a zoom of 1.0, matching the window in `config/display.ron`
+pub const VIEW_WIDTH: f32 = 1200.0;
+pub const VIEW_HEIGHT: f32 = 800.0;
+
+// closest the camera will zoom in, as a fraction of the base view
+const MIN_ZOOM: f32 = 0.6;
+// space kept around the ships at the edge of the view
+const VIEW_PADDING: f32 = 300.0;
+// how quickly the camera closes on its target, higher is snappier
+const CAMERA_SMOOTHING: f32 = 3.0;
+// furthest the camera is thrown by a full-strength shake, in pixels at a zoom of 1.0
+const MAX_SHAKE: f32 = 24.0;
+
+/// Pans and zooms the camera to keep every ship in view, Star Control style.
+/// Ships are measured across the shortest wrapped distance, so two ships either side
+/// of a wrapping edge are framed together rather than from opposite ends of the arena.
+/// Screen shake is added on top of the eased position, which is kept separately so the
+/// shake never drags the camera off course.
+#[derive(SystemDesc, Default)]
+pub struct CameraSystem {
+    focus: Option<(f32, f32)>,
+}
+
+impl<'s> System<'s> for CameraSystem {
+    type SystemData = (
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Camera>,
+        WriteStorage<'s, Transform>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, ScreenShake>,
+        Read<'s, FeedbackSettings>,
+        ReadExpect<'s, RandomGen>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (ships, cameras, mut transforms, arena, shake, settings, random_gen, time): Self::SystemData) {
+        let arena_state = arena.state();
+
+        let positions: Vec<(f32, f32)> = (&ships, &transforms).join()
+            .map(|(_, transform)| (transform.translation().x, transform.translation().y))
+            .collect();
+
+        let (target_x, target_y, target_zoom) = match frame_ships(&arena_state, &positions) {
+            Some(target) => target,
+            None => return,
+        };
+
+        // exponential smoothing, so the camera eases in at any frame rate
+        let blend = 1.0 - (-CAMERA_SMOOTHING * time.delta_seconds()).exp();
+
+        // squaring the trauma keeps light hits subtle and heavy ones violent
+        let shake_strength = if settings.screen_shake {
+            shake.trauma * shake.trauma * MAX_SHAKE
+        } else {
+            0.0
+        };
+
+        for (_, transform) in (&cameras, &mut transforms).join() {
+            let (x, y) = self.focus
+                .unwrap_or((transform.translation().x, transform.translation().y));
+            let zoom = transform.scale().x;
+
+            // pan the short way round so the camera follows ships across a wrapping edge
+            let (dx, dy) = arena_state.delta(x, y, target_x, target_y);
+            let zoom = zoom + (target_zoom - zoom) * blend;
+            let (x, y) = keep_in_arena(&arena_state, x + dx * blend, y + dy * blend, zoom);
+            self.focus = Some((x, y));
+
+            let shake_x = (random_gen.next_f32() * 2.0 - 1.0) * shake_strength * zoom;
+            let shake_y = (random_gen.next_f32() * 2.0 - 1.0) * shake_strength * zoom;
+
+            transform.set_translation_x(x + shake_x);
+            transform.set_translation_y(y + shake_y);
+            transform.set_scale(Vector3::new(zoom, zoom, 1.0));
+        }
+    }
+}
+
+/// Centre and zoom that fit every position in view, or None if there is nothing to frame
+pub fn frame_ships(arena: &ArenaState, positions: &[(f32, f32)]) -> Option<(f32, f32, f32)> {
+    let (anchor_x, anchor_y) = *positions.first()?;
+
+    // bounding box of every ship relative to the first
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in positions.iter().skip(1) {
+        let (dx, dy) = arena.delta(anchor_x, anchor_y, *x, *y);
+        min_x = min_x.min(dx);
+        max_x = max_x.max(dx);
+        min_y = min_y.min(dy);
+        max_y = max_y.max(dy);
+    }
+
+    let zoom = ((max_x - min_x + VIEW_PADDING * 2.0) / VIEW_WIDTH)
+        .max((max_y - min_y + VIEW_PADDING * 2.0) / VIEW_HEIGHT)
+        .max(MIN_ZOOM)
+        .min(max_zoom(arena));
+
+    Some((anchor_x + (min_x + max_x) / 2.0, anchor_y + (min_y + max_y) / 2.0, zoom))
+}
+
+/// Zoom at which the whole arena fits in view
+pub fn max_zoom(arena: &ArenaState) -> f32 {
+    (arena.width / VIEW_WIDTH).max(arena.height / VIEW_HEIGHT)
+}
+
+/// Wraps the camera centre back into a wrapping arena, or stops a walled arena's view
+/// from running past its walls
+fn keep_in_arena(arena: &ArenaState, x: f32, y: f32, zoom: f32) -> (f32, f32) {
+    if arena.wraps {
+        return (x.rem_euclid(arena.width), y.rem_euclid(arena.height));
+    }
+
+    (
+        clamp_axis(x, VIEW_WIDTH * zoom, arena.width),
+        clamp_axis(y, VIEW_HEIGHT * zoom, arena.height),
+    )
+}
+
+fn clamp_axis(centre: f32, view: f32, size: f32) -> f32 {
+    if view >= size {
+        size / 2.0
+    } else {
+        centre.max(view / 2.0).min(size - view / 2.0)
+    }
+}
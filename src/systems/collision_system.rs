@@ -1,9 +1,9 @@
 use amethyst::{
     assets::AssetStorage,
     audio::{output::Output, Source},
-    core::{Transform, SystemDesc},
+    core::{Transform, SystemDesc, math::Vector2},
     derive::SystemDesc,
-    ecs::prelude::{Join, ReadStorage, Read, ReadExpect, Entities, System, SystemData, World, WriteStorage, LazyUpdate},
+    ecs::prelude::{Entity, Join, ReadStorage, Read, ReadExpect, Entities, System, SystemData, World, Write, WriteStorage, LazyUpdate},
 };
 
 use std::ops::Deref;
@@ -11,8 +11,16 @@ use std::ops::Deref;
 use crate::states::{RandomGen, LASER_RADIUS};
 use crate::audio::{play_impact_sound, Sounds};
 use crate::systems::laser::show_laser_impact;
-use crate::components::{Laser, Ship, Physical, Combat};
-use crate::resources::{PrefabList, AssetType};
+use crate::components::{Laser, Ship, Physical, Combat, Special, Shield, DamageType, GravityWell,
+    Asteroid, HULL_SPARKS, spawn_burst};
+use crate::resources::{PrefabList, SpriteSheetList, PREFAB_LASER_IMPACT, SPRITE_PARTICLE, ArenaDefinition, ArenaState, ScreenShake, CombatLog, Weapon};
+
+// sparks thrown off by a laser hit
+const SPARK_COUNT: usize = 10;
+
+pub const COLLISION_DAMAGE: i32 = 30;
+// momentum a laser passes on to an asteroid it hits
+const LASER_PUSH: f32 = 1.0;
 
 #[derive(SystemDesc)]
 pub struct CollisionSystem;
@@ -25,6 +33,13 @@ impl<'s> System<'s> for CollisionSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Physical>,
         WriteStorage<'s, Combat>,
+        ReadStorage<'s, Special>,
+        WriteStorage<'s, Shield>,
+        ReadStorage<'s, GravityWell>,
+        WriteStorage<'s, Asteroid>,
+        Read<'s, ArenaDefinition>,
+        Write<'s, ScreenShake>,
+        Write<'s, CombatLog>,
         ReadExpect<'s, RandomGen>,
 
         Read<'s, AssetStorage<Source>>,
@@ -32,6 +47,7 @@ impl<'s> System<'s> for CollisionSystem {
         Option<Read<'s, Output>>,
         ReadExpect<'s, LazyUpdate>,
         ReadExpect<'s, PrefabList>,
+        Read<'s, SpriteSheetList>,
     );
 
     fn run(&mut self, (
@@ -41,6 +57,13 @@ impl<'s> System<'s> for CollisionSystem {
         mut transforms, 
         mut physicals, 
         mut combat, 
+        specials,
+        mut shields,
+        gravity_wells,
+        mut asteroids,
+        arena,
+        mut shake,
+        mut combat_log,
         random_gen, 
 
         storage,
@@ -48,7 +71,10 @@ impl<'s> System<'s> for CollisionSystem {
         audio_output,
         lazy_update,
         prefab_list,
+        sprite_sheet_list,
     ): Self::SystemData) {
+
+        let arena = arena.state();
         
         // laser collision
         for (laser, entity) in (&lasers, &entities).join() {
@@ -64,11 +90,14 @@ impl<'s> System<'s> for CollisionSystem {
                 phys.velocity.clone()
             };
 
-            for (ship, ship_transform, combat, physical) in (&ships, &mut transforms, &mut combat, &mut physicals).join() {
+            let mut hit = false;
+
+            for (ship_entity, ship, ship_transform, combat, physical, special, shield) in (&entities, &ships, &mut transforms, &mut combat, &mut physicals, specials.maybe(), (&mut shields).maybe()).join() {
                 let ship_x = ship_transform.translation().x;
                 let ship_y = ship_transform.translation().y;
 
                 if circles_collide(
+                    &arena,
                     laser_transform.translation().x,
                     laser_transform.translation().y,
                     LASER_RADIUS,
@@ -77,25 +106,44 @@ impl<'s> System<'s> for CollisionSystem {
                     ship_y,
                     physical.radius,
                 ) {
-                    // damage ship hit
-                    let mut damage = laser.damage - combat.armour;
-                    if damage <= 0 {
-                        damage = 0;
-                    };
+                    // damage ship hit through shield and armour
+                    if !is_shielded(special) {
+                        let report = combat.take_damage(shield, laser.damage, DamageType::Laser);
+                        combat_log.hit(
+                            ship_entity,
+                            Some(laser.side),
+                            laser_transform.translation().x,
+                            laser_transform.translation().y,
+                            report,
+                            Weapon::Laser,
+                        );
+                    }
+                    shake.impact(laser.damage as f32);
 
-                    combat.structure -= damage;
                     play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
 
-                    let laser_impact_prefab_handle = {
-                        prefab_list.get(AssetType::LaserImpact).unwrap().clone()
-                    };
+                    if let Some(laser_impact_prefab_handle) = prefab_list.get(PREFAB_LASER_IMPACT) {
+                        show_laser_impact(
+                            &entities,
+                            laser_impact_prefab_handle.clone(),
+                            laser_transform.clone(),
+                            &lazy_update,
+                        );
+                    }
 
-                    show_laser_impact(
-                        &entities,
-                        laser_impact_prefab_handle,
-                        laser_transform.clone(),
-                        &lazy_update,
-                    );
+                    if let Some(sprite_sheet) = sprite_sheet_list.get(SPRITE_PARTICLE) {
+                        spawn_burst(
+                            &entities,
+                            &lazy_update,
+                            sprite_sheet.clone(),
+                            &random_gen,
+                            &HULL_SPARKS,
+                            SPARK_COUNT,
+                            laser_transform.translation().x,
+                            laser_transform.translation().y,
+                            physical.velocity,
+                        );
+                    }
 
                     if combat.structure <= 0 {
                         // explode ship and delete
@@ -111,16 +159,199 @@ impl<'s> System<'s> for CollisionSystem {
                     }
                     // delete laser
                     entities.delete(entity).expect("Unable to delete laser");
+                    hit = true;
+                }
+            }
+
+            if hit {
+                continue;
+            }
+
+            for (asteroid, asteroid_transform, physical) in (&mut asteroids, &transforms, &mut physicals).join() {
+                let offset = surface_offset(&arena, &laser_transform, asteroid_transform.translation().x, asteroid_transform.translation().y);
+
+                if offset.magnitude() <= physical.radius + LASER_RADIUS {
+                    asteroid.structure -= laser.damage;
+                    // rock chips barely register next to a hit on a ship
+                    shake.impact(laser.damage as f32 * 0.25);
+
+                    // heavier asteroids are harder to knock about
+                    physical.velocity += laser_vel * (LASER_PUSH / physical.mass);
+
+                    play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+
+                    if let Some(laser_impact_prefab_handle) = prefab_list.get(PREFAB_LASER_IMPACT) {
+                        show_laser_impact(
+                            &entities,
+                            laser_impact_prefab_handle.clone(),
+                            laser_transform.clone(),
+                            &lazy_update,
+                        );
+                    }
+
+                    entities.delete(entity).expect("Unable to delete laser");
+                    break;
                 }
             }
         }
+
+        // asteroid collisions
+        let asteroid_bodies: Vec<(Entity, f32, f32, Physical, i32)> = (&entities, &asteroids, &transforms, &physicals).join()
+            .map(|(entity, asteroid, transform, physical)| {
+                (entity, transform.translation().x, transform.translation().y, *physical, asteroid.damage)
+            })
+            .collect();
+
+        // velocity changes for asteroids, applied once the ships are done with the storage
+        let mut asteroid_impulses: Vec<(Entity, Vector2<f32>)> = Vec::new();
+
+        for (asteroid_entity, asteroid_x, asteroid_y, asteroid_physical, asteroid_damage) in asteroid_bodies {
+            for (ship_entity, ship, ship_transform, combat, physical, special, shield) in (&entities, &ships, &mut transforms, &mut combat, &mut physicals, specials.maybe(), (&mut shields).maybe()).join() {
+                let offset = surface_offset(&arena, ship_transform, asteroid_x, asteroid_y);
+                let surface = asteroid_physical.radius + physical.radius;
+
+                if offset.magnitude() > surface || offset.magnitude() == 0.0 {
+                    continue;
+                }
+
+                let normal = offset.normalize();
+                let closing = (physical.velocity - asteroid_physical.velocity).dot(&normal);
+
+                if closing < 0.0 {
+                    if !is_shielded(special) {
+                        let report = combat.take_damage(shield, asteroid_damage, DamageType::Collision);
+                        combat_log.hit(
+                            ship_entity,
+                            None,
+                            ship_transform.translation().x,
+                            ship_transform.translation().y,
+                            report,
+                            Weapon::Asteroid,
+                        );
+                    }
+
+                    if let Some(asteroid) = asteroids.get_mut(asteroid_entity) {
+                        asteroid.structure -= COLLISION_DAMAGE;
+                    }
+                    shake.impact(asteroid_damage as f32);
+
+                    play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+
+                    if combat.structure <= 0 {
+                        println!("{:?} ship was smashed by an asteroid!", ship.side);
+                    }
+
+                    // elastic bounce, shared out by mass
+                    let impulse = -2.0 * closing / (1.0 / physical.mass + 1.0 / asteroid_physical.mass);
+                    physical.velocity += normal * (impulse / physical.mass);
+                    asteroid_impulses.push((asteroid_entity, -normal * (impulse / asteroid_physical.mass)));
+                }
+
+                // push the ship back out of the asteroid
+                let push = normal * (surface - offset.magnitude());
+                ship_transform.prepend_translation_x(push[0]);
+                ship_transform.prepend_translation_y(push[1]);
+            }
+        }
+
+        for (asteroid_entity, impulse) in asteroid_impulses {
+            if let Some(physical) = physicals.get_mut(asteroid_entity) {
+                physical.velocity += impulse;
+            }
+        }
+        // planet collisions
+        let wells: Vec<(f32, f32, GravityWell)> = (&transforms, &gravity_wells).join()
+            .map(|(transform, well)| (transform.translation().x, transform.translation().y, *well))
+            .collect();
+
+        for (well_x, well_y, well) in wells {
+
+            // lasers burn up on the surface
+            for (entity, _, laser_transform) in (&entities, &lasers, &transforms).join() {
+                let offset = surface_offset(&arena, laser_transform, well_x, well_y);
+
+                if offset.magnitude() <= well.radius + LASER_RADIUS {
+                    // the laser may already have been deleted by a ship hit this frame
+                    let _ = entities.delete(entity);
+                }
+            }
+
+            for (ship_entity, ship, ship_transform, combat, physical, special, shield) in (&entities, &ships, &mut transforms, &mut combat, &mut physicals, specials.maybe(), (&mut shields).maybe()).join() {
+                let offset = surface_offset(&arena, ship_transform, well_x, well_y);
+                let surface = well.radius + physical.radius;
+
+                if offset.magnitude() > surface || offset.magnitude() == 0.0 {
+                    continue;
+                }
+
+                let normal = offset.normalize();
+
+                // only damage ships moving into the planet, not ones resting on it
+                if physical.velocity.dot(&normal) < 0.0 {
+                    if !is_shielded(special) {
+                        let report = combat.take_damage(shield, well.damage, DamageType::Collision);
+                        combat_log.hit(
+                            ship_entity,
+                            None,
+                            ship_transform.translation().x,
+                            ship_transform.translation().y,
+                            report,
+                            Weapon::Planet,
+                        );
+                    }
+                    shake.impact(well.damage as f32);
+
+                    play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+
+                    if combat.structure <= 0 {
+                        println!("{:?} ship crashed into a planet!", ship.side);
+                    }
+
+                    // bounce off the surface
+                    physical.velocity -= normal * (2.0 * physical.velocity.dot(&normal));
+                }
+
+                // push the ship back out to the surface
+                let push = normal * (surface - offset.magnitude());
+                ship_transform.prepend_translation_x(push[0]);
+                ship_transform.prepend_translation_y(push[1]);
+            }
+
+            // asteroids bounce off planets unharmed
+            for (_, asteroid_transform, physical) in (&asteroids, &mut transforms, &mut physicals).join() {
+                let offset = surface_offset(&arena, asteroid_transform, well_x, well_y);
+                let surface = well.radius + physical.radius;
+
+                if offset.magnitude() > surface || offset.magnitude() == 0.0 {
+                    continue;
+                }
+
+                let normal = offset.normalize();
+
+                if physical.velocity.dot(&normal) < 0.0 {
+                    physical.velocity -= normal * (2.0 * physical.velocity.dot(&normal));
+                }
+
+                let push = normal * (surface - offset.magnitude());
+                asteroid_transform.prepend_translation_x(push[0]);
+                asteroid_transform.prepend_translation_y(push[1]);
+            }
+        }
+
         // check for ship collisions
-        let mut ships_iter = (&ships, &mut physicals, &mut combat, &mut transforms).join();
+        let mut ships_iter = (&entities, &ships, &mut physicals, &mut combat, &mut transforms, specials.maybe(), (&mut shields).maybe()).join();
         
-        let (light_ship, mut light_physical, mut light_combat, light_transform) = ships_iter.next().unwrap();
-        let (dark_ship, mut dark_physical, mut dark_combat, dark_transform) = ships_iter.next().unwrap();
+        let (light, dark) = match (ships_iter.next(), ships_iter.next()) {
+            (Some(light), Some(dark)) => (light, dark),
+            // a ship is missing, between matches for one, so there is nothing to collide
+            _ => return,
+        };
+
+        let (light_entity, light_ship, mut light_physical, mut light_combat, light_transform, light_special, light_shield) = light;
+        let (dark_entity, dark_ship, mut dark_physical, mut dark_combat, dark_transform, dark_special, dark_shield) = dark;
 
         if circles_collide(
+            &arena,
             light_transform.translation().x,
             light_transform.translation().y,
             light_physical.radius,
@@ -131,19 +362,30 @@ impl<'s> System<'s> for CollisionSystem {
         ) {
 
             // track impact on light & dark ships
-            let mut light_damage = 30 - light_combat.armour;
-            if light_damage <= 0 {
-                light_damage = 0;
-            };
-
-            light_combat.structure -= light_damage;
-
-            let mut dark_damage = 30 - dark_combat.armour;
-            if dark_damage <= 0 {
-                dark_damage = 0;
-            };
+            if !is_shielded(light_special) {
+                let report = light_combat.take_damage(light_shield, COLLISION_DAMAGE, DamageType::Collision);
+                combat_log.hit(
+                    light_entity,
+                    Some(dark_ship.side),
+                    light_transform.translation().x,
+                    light_transform.translation().y,
+                    report,
+                    Weapon::Ram,
+                );
+            }
 
-            dark_combat.structure -= dark_damage;
+            if !is_shielded(dark_special) {
+                let report = dark_combat.take_damage(dark_shield, COLLISION_DAMAGE, DamageType::Collision);
+                combat_log.hit(
+                    dark_entity,
+                    Some(light_ship.side),
+                    dark_transform.translation().x,
+                    dark_transform.translation().y,
+                    report,
+                    Weapon::Ram,
+                );
+            }
+            shake.impact(COLLISION_DAMAGE as f32);
 
             play_impact_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
              
@@ -176,14 +418,25 @@ impl<'s> System<'s> for CollisionSystem {
     }
 }
 
+fn is_shielded(special: Option<&Special>) -> bool {
+    special.map_or(false, |special| special.is_shielding())
+}
+
+/// Shortest wrapped offset from a planet centre to the transform
+fn surface_offset(arena: &ArenaState, transform: &Transform, well_x: f32, well_y: f32) -> Vector2<f32> {
+    let (dx, dy) = arena.delta(well_x, well_y, transform.translation().x, transform.translation().y);
+    Vector2::new(dx, dy)
+}
+
 fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
     x >= left && x <= right && y>= bottom && y <= top
 }
 
-fn circles_collide(a_x: f32, a_y: f32, a_r: f32, b_x: f32, b_y: f32, b_r: f32) -> bool {
+/// Whether two circles overlap, measured the shortest way, so across the edge of a wrapping
+/// arena where the ghost of one is drawn touching the other
+fn circles_collide(arena: &ArenaState, a_x: f32, a_y: f32, a_r: f32, b_x: f32, b_y: f32, b_r: f32) -> bool {
 
-    let dx = a_x - b_x;
-    let dy = a_y - b_y;
+    let (dx, dy) = arena.delta(a_x, a_y, b_x, b_y);
 
     let distance = dx * dx + dy * dy;
 
@@ -192,4 +445,29 @@ fn circles_collide(a_x: f32, a_y: f32, a_r: f32, b_x: f32, b_y: f32, b_r: f32) -
     } else {
         true
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena(wraps: bool) -> ArenaState {
+        ArenaState { width: 1000.0, height: 800.0, wraps, margin: 0.0 }
+    }
+
+    #[test]
+    fn circles_collide_across_a_wrapping_edge() {
+        assert!(circles_collide(&arena(true), 2.0, 400.0, LASER_RADIUS, 990.0, 400.0, 40.0));
+        assert!(circles_collide(&arena(true), 500.0, 795.0, 40.0, 500.0, 10.0, 40.0));
+    }
+
+    #[test]
+    fn circles_do_not_collide_through_walls() {
+        assert!(!circles_collide(&arena(false), 2.0, 400.0, LASER_RADIUS, 990.0, 400.0, 40.0));
+    }
+
+    #[test]
+    fn distant_circles_do_not_collide() {
+        assert!(!circles_collide(&arena(true), 100.0, 400.0, LASER_RADIUS, 500.0, 400.0, 40.0));
+    }
+}
@@ -0,0 +1,24 @@
+use amethyst::{
+    core::{SystemDesc, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, World, WriteStorage},
+};
+
+use crate::components::Energy;
+
+/// Regenerates ship energy
+#[derive(SystemDesc)]
+pub struct EnergySystem;
+
+impl<'s> System<'s> for EnergySystem {
+    type SystemData = (
+        WriteStorage<'s, Energy>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut energies, time): Self::SystemData) {
+        for energy in (&mut energies).join() {
+            energy.regenerate(time.delta_seconds());
+        }
+    }
+}
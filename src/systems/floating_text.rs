@@ -0,0 +1,173 @@
+use amethyst::{
+    core::{Transform, SystemDesc, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, Read, ReadExpect, ReadStorage, System, SystemData, World, Write, WriteStorage},
+    renderer::Camera,
+    ui::{Anchor, UiText, UiTransform},
+};
+
+use crate::components::{FloatingText, Hud};
+use crate::resources::{ArenaDefinition, CombatLog, Hit, Weapon};
+
+// seconds a damage number stays up
+const DAMAGE_TEXT_LIFETIME: f32 = 0.9;
+// how far a damage number rises over its life, in arena pixels
+const DAMAGE_TEXT_RISE: f32 = 60.0;
+// damage numbers grow with the hit, up to this much damage
+const MAX_SIZED_DAMAGE: f32 = 40.0;
+
+// damage that went straight into the hull
+const FULL_DAMAGE_COLOUR: [f32; 3] = [1.0, 0.9, 0.3];
+// damage that armour took the edge off, or stopped entirely
+const ARMOURED_COLOUR: [f32; 3] = [0.7, 0.78, 0.9];
+// hits soaked up by a shield
+const ABSORBED_COLOUR: [f32; 3] = [0.4, 0.85, 1.0];
+// the danger zone burning a ship
+const ZONE_COLOUR: [f32; 3] = [1.0, 0.45, 0.3];
+
+/// Throws up a damage number wherever a hit landed this frame, then floats each number
+/// upward and fades it out. Numbers are pinned to the arena, so they follow the camera.
+#[derive(SystemDesc)]
+pub struct FloatingTextSystem;
+
+impl<'s> System<'s> for FloatingTextSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, FloatingText>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        Write<'s, CombatLog>,
+        ReadExpect<'s, Hud>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        mut floating_texts,
+        mut ui_transforms,
+        mut ui_texts,
+        cameras,
+        transforms,
+        mut combat_log,
+        hud,
+        arena,
+        time,
+    ): Self::SystemData) {
+
+        for hit in combat_log.hits.drain(..) {
+            let (text, colour) = match damage_text(&hit) {
+                Some(label) => label,
+                None => continue,
+            };
+            let size = 18.0 + (hit.report.dealt as f32).min(MAX_SIZED_DAMAGE) * 0.3;
+
+            let entity = entities.create();
+            floating_texts.insert(entity, FloatingText::new(hit.x, hit.y, DAMAGE_TEXT_LIFETIME, colour))
+                .expect("Unable to create damage text");
+            ui_transforms.insert(entity, UiTransform::new(
+                "DamageText".to_string(), Anchor::Middle, Anchor::Middle,
+                0.0, 0.0, 3.0, 240.0, 30.0,
+            )).expect("Unable to place damage text");
+            ui_texts.insert(entity, UiText::new(hud.font.clone(), text, [colour[0], colour[1], colour[2], 1.0], size))
+                .expect("Unable to write damage text");
+        }
+
+        let (camera_x, camera_y, zoom) = match (&cameras, &transforms).join().next() {
+            Some((_, transform)) => (transform.translation().x, transform.translation().y, transform.scale().x),
+            None => return,
+        };
+        let arena_state = arena.state();
+
+        // real time, so numbers still rise through a hit-stop
+        let delta = time.delta_real_seconds();
+
+        for (entity, floating_text, ui_transform, ui_text) in (&entities, &mut floating_texts, &mut ui_transforms, &mut ui_texts).join() {
+            floating_text.age += delta;
+
+            if floating_text.age >= floating_text.lifetime {
+                let _ = entities.delete(entity);
+                continue;
+            }
+
+            let progress = floating_text.progress();
+            let y = floating_text.y + DAMAGE_TEXT_RISE * progress;
+
+            // screen pixels from the centre of the view, the short way round a wrapping arena
+            let (dx, dy) = arena_state.delta(camera_x, camera_y, floating_text.x, y);
+            ui_transform.local_x = dx / zoom;
+            ui_transform.local_y = dy / zoom;
+
+            let [r, g, b] = floating_text.colour;
+            ui_text.color = [r, g, b, 1.0 - progress * progress];
+        }
+    }
+}
+
+/// The number to show for a hit and its colour, or None if there is nothing worth showing
+fn damage_text(hit: &Hit) -> Option<(String, [f32; 3])> {
+    let report = hit.report;
+
+    if report.raw <= 0 {
+        return None;
+    }
+
+    let label = if hit.weapon == Weapon::DangerZone {
+        (format!("-{}", report.dealt), ZONE_COLOUR)
+    } else if report.dealt > 0 && report.blocked > 0 {
+        (format!("-{} ({} ARMOUR)", report.dealt, report.blocked), ARMOURED_COLOUR)
+    } else if report.dealt > 0 {
+        (format!("-{}", report.dealt), FULL_DAMAGE_COLOUR)
+    } else if report.blocked > 0 {
+        ("ARMOUR".to_string(), ARMOURED_COLOUR)
+    } else {
+        ("ABSORBED".to_string(), ABSORBED_COLOUR)
+    };
+
+    Some(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::DamageReport;
+
+    fn hit(weapon: Weapon, raw: i32, absorbed: i32, blocked: i32, dealt: i32) -> Hit {
+        Hit { x: 0.0, y: 0.0, report: DamageReport { raw, absorbed, blocked, dealt }, weapon }
+    }
+
+    #[test]
+    fn full_damage_shows_the_number() {
+        assert_eq!(damage_text(&hit(Weapon::Laser, 12, 0, 0, 12)), Some(("-12".to_string(), FULL_DAMAGE_COLOUR)));
+    }
+
+    #[test]
+    fn armour_reduced_damage_shows_what_was_blocked() {
+        assert_eq!(
+            damage_text(&hit(Weapon::Laser, 12, 0, 3, 9)),
+            Some(("-9 (3 ARMOUR)".to_string(), ARMOURED_COLOUR)),
+        );
+    }
+
+    #[test]
+    fn fully_blocked_damage_shows_armour() {
+        assert_eq!(damage_text(&hit(Weapon::Ram, 3, 0, 3, 0)), Some(("ARMOUR".to_string(), ARMOURED_COLOUR)));
+    }
+
+    #[test]
+    fn shielded_damage_shows_absorbed() {
+        assert_eq!(damage_text(&hit(Weapon::Laser, 10, 10, 0, 0)), Some(("ABSORBED".to_string(), ABSORBED_COLOUR)));
+    }
+
+    #[test]
+    fn danger_zone_damage_has_its_own_colour() {
+        assert_eq!(damage_text(&hit(Weapon::DangerZone, 5, 0, 2, 3)), Some(("-3".to_string(), ZONE_COLOUR)));
+    }
+
+    #[test]
+    fn empty_hits_show_nothing() {
+        assert_eq!(damage_text(&hit(Weapon::Asteroid, 0, 0, 0, 0)), None);
+    }
+}
@@ -0,0 +1,243 @@
+use amethyst::{
+    core::{Hidden, Parent, Transform, SystemDesc},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::{SpriteRender, Transparent, resources::Tint},
+};
+
+use std::collections::HashMap;
+
+use crate::components::{Ghost, Physical};
+use crate::resources::ArenaDefinition;
+
+// how far past its radius a body is drawn on the far side, covering thrusters and sprite corners
+const GHOST_BAND: f32 = 60.0;
+
+/// Which copy of the arena a ghost is drawn in, as whole arena widths and heights
+type Offset = (i8, i8);
+
+/// A ghost of one entity, and ghosts of its drawn children such as the thruster
+struct GhostCopy {
+    offset: Offset,
+    ghost: Entity,
+    children: Vec<(Entity, Entity)>,
+}
+
+/// Draws ghost copies of ships, lasers and asteroids that straddle the edge of a wrapping
+/// arena on the far side too, so they slide smoothly across instead of popping over.
+/// Ghosts follow their source's transform, sprite, tint and visibility every frame.
+#[derive(SystemDesc, Default)]
+pub struct GhostSystem {
+    ghosts: HashMap<Entity, Vec<GhostCopy>>,
+}
+
+impl<'s> System<'s> for GhostSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ghost>,
+        ReadStorage<'s, Physical>,
+        WriteStorage<'s, Parent>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Transparent>,
+        Read<'s, ArenaDefinition>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        mut ghosts,
+        physicals,
+        mut parents,
+        mut transforms,
+        mut sprite_renders,
+        mut hiddens,
+        mut tints,
+        mut transparents,
+        arena,
+    ): Self::SystemData) {
+
+        // every copy each body needs this frame, none at all if the arena has walls
+        let mut wanted: HashMap<Entity, Vec<Offset>> = HashMap::new();
+
+        if arena.wraps() {
+            for (entity, physical, transform, _) in (&entities, &physicals, &transforms, &sprite_renders).join() {
+                let offsets = ghost_offsets(
+                    transform.translation().x,
+                    transform.translation().y,
+                    physical.radius + GHOST_BAND,
+                    arena.width,
+                    arena.height,
+                );
+
+                if !offsets.is_empty() {
+                    wanted.insert(entity, offsets);
+                }
+            }
+        }
+
+        // drop ghosts whose source has left the edge or been destroyed
+        for (source, copies) in self.ghosts.iter_mut() {
+            let offsets = wanted.get(source);
+
+            copies.retain(|copy| {
+                if offsets.map_or(false, |offsets| offsets.contains(&copy.offset)) {
+                    return true;
+                }
+
+                for (_, child) in copy.children.iter() {
+                    let _ = entities.delete(*child);
+                }
+                let _ = entities.delete(copy.ghost);
+                false
+            });
+        }
+        self.ghosts.retain(|_, copies| !copies.is_empty());
+
+        for (source, offsets) in wanted {
+            // drawn children of the source, skipping the children of its own ghosts
+            let children: Vec<Entity> = (&entities, &parents, &sprite_renders, !&ghosts).join()
+                .filter(|(_, parent, _, _)| parent.entity == source)
+                .map(|(child, _, _, _)| child)
+                .collect();
+
+            let copies = self.ghosts.entry(source).or_insert_with(Vec::new);
+
+            for offset in offsets {
+                if !copies.iter().any(|copy| copy.offset == offset) {
+                    let ghost = entities.create();
+                    ghosts.insert(ghost, Ghost { source }).expect("Unable to create ghost");
+                    copy_transparency(source, ghost, &mut transparents);
+
+                    let children = children.iter()
+                        .map(|child| {
+                            let child_ghost = entities.create();
+                            ghosts.insert(child_ghost, Ghost { source: *child }).expect("Unable to create ghost");
+                            parents.insert(child_ghost, Parent::new(ghost)).expect("Unable to parent ghost");
+                            copy_transparency(*child, child_ghost, &mut transparents);
+                            (*child, child_ghost)
+                        })
+                        .collect();
+
+                    copies.push(GhostCopy { offset, ghost, children });
+                }
+            }
+
+            // keep every copy in step with its source
+            for copy in copies.iter() {
+                if let Some(mut transform) = transforms.get(source).cloned() {
+                    transform.prepend_translation_x(copy.offset.0 as f32 * arena.width);
+                    transform.prepend_translation_y(copy.offset.1 as f32 * arena.height);
+                    transforms.insert(copy.ghost, transform).expect("Unable to move ghost");
+                }
+                copy_appearance(source, copy.ghost, &mut sprite_renders, &mut hiddens, &mut tints);
+
+                for (child, child_ghost) in copy.children.iter() {
+                    // children keep their transform relative to the parent
+                    if let Some(transform) = transforms.get(*child).cloned() {
+                        transforms.insert(*child_ghost, transform).expect("Unable to move ghost");
+                    }
+                    copy_appearance(*child, *child_ghost, &mut sprite_renders, &mut hiddens, &mut tints);
+                }
+            }
+        }
+    }
+}
+
+/// Arena copies a body at (x, y) should also be drawn in, given how far it reaches
+pub fn ghost_offsets(x: f32, y: f32, reach: f32, width: f32, height: f32) -> Vec<Offset> {
+    let across = edge_offset(x, reach, width);
+    let down = edge_offset(y, reach, height);
+
+    let mut offsets = Vec::new();
+    if across != 0 {
+        offsets.push((across, 0));
+    }
+    if down != 0 {
+        offsets.push((0, down));
+    }
+    // a body in a corner shows in the diagonal corner too
+    if across != 0 && down != 0 {
+        offsets.push((across, down));
+    }
+
+    offsets
+}
+
+fn edge_offset(position: f32, reach: f32, size: f32) -> i8 {
+    if position < reach {
+        1
+    } else if position > size - reach {
+        -1
+    } else {
+        0
+    }
+}
+
+fn copy_transparency(from: Entity, to: Entity, transparents: &mut WriteStorage<Transparent>) {
+    if transparents.contains(from) {
+        transparents.insert(to, Transparent).expect("Unable to copy transparency");
+    }
+}
+
+fn copy_appearance(
+    from: Entity,
+    to: Entity,
+    sprite_renders: &mut WriteStorage<SpriteRender>,
+    hiddens: &mut WriteStorage<Hidden>,
+    tints: &mut WriteStorage<Tint>,
+) {
+    if let Some(sprite_render) = sprite_renders.get(from).cloned() {
+        sprite_renders.insert(to, sprite_render).expect("Unable to copy sprite");
+    }
+
+    if hiddens.contains(from) {
+        hiddens.insert(to, Hidden).expect("Unable to copy visibility");
+    } else {
+        hiddens.remove(to);
+    }
+
+    match tints.get(from).cloned() {
+        Some(tint) => {
+            tints.insert(to, tint).expect("Unable to copy tint");
+        }
+        None => {
+            tints.remove(to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interior_bodies_have_no_ghosts() {
+        assert!(ghost_offsets(500.0, 400.0, 80.0, 1000.0, 800.0).is_empty());
+    }
+
+    #[test]
+    fn bodies_near_an_edge_show_on_the_far_side() {
+        assert_eq!(ghost_offsets(30.0, 400.0, 80.0, 1000.0, 800.0), vec![(1, 0)]);
+        assert_eq!(ghost_offsets(980.0, 400.0, 80.0, 1000.0, 800.0), vec![(-1, 0)]);
+        assert_eq!(ghost_offsets(500.0, 10.0, 80.0, 1000.0, 800.0), vec![(0, 1)]);
+        assert_eq!(ghost_offsets(500.0, 790.0, 80.0, 1000.0, 800.0), vec![(0, -1)]);
+    }
+
+    #[test]
+    fn bodies_in_a_corner_also_show_diagonally() {
+        assert_eq!(
+            ghost_offsets(970.0, 20.0, 80.0, 1000.0, 800.0),
+            vec![(-1, 0), (0, 1), (-1, 1)],
+        );
+    }
+
+    #[test]
+    fn edge_offset_is_exclusive_at_the_reach() {
+        assert_eq!(edge_offset(80.0, 80.0, 1000.0), 0);
+        assert_eq!(edge_offset(920.0, 80.0, 1000.0), 0);
+        assert_eq!(edge_offset(79.0, 80.0, 1000.0), 1);
+        assert_eq!(edge_offset(921.0, 80.0, 1000.0), -1);
+    }
+}
@@ -0,0 +1,184 @@
+use amethyst::{
+    core::SystemDesc,
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, World, WriteExpect, WriteStorage},
+    ui::{UiImage, UiText, UiTransform},
+};
+
+use crate::components::{Combat, DangerZone, Energy, Hud, HudBar, HudBarKind, HudStorages, ScoreBoard, Shield,
+    Ship, Special, HUD_BAR_WIDTH, create_player_hud};
+use crate::resources::{ArenaDefinition, CombatLog, EdgeMode};
+
+// smallest change in a bar worth resizing it for
+const BAR_EPSILON: f32 = 0.001;
+
+/// Owns everything the HUD shows. Each ship gets a panel of bars for its structure, shield,
+/// energy and weapon reload, a readout of its special ability and its side's score. The
+/// danger zone countdown is shown in arenas that have one, and the kill feed lists the
+/// latest round results.
+/// Panels are added as ships appear and dropped when they go, so any number of players fit.
+#[derive(SystemDesc)]
+pub struct HudSystem;
+
+impl<'s> System<'s> for HudSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Combat>,
+        ReadStorage<'s, Energy>,
+        ReadStorage<'s, Shield>,
+        ReadStorage<'s, Special>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, UiText>,
+        WriteExpect<'s, Hud>,
+        Read<'s, ScoreBoard>,
+        Read<'s, DangerZone>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, CombatLog>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        combats,
+        energies,
+        shields,
+        specials,
+        mut ui_transforms,
+        mut ui_images,
+        mut ui_texts,
+        mut hud,
+        scores,
+        zone,
+        arena,
+        combat_log,
+    ): Self::SystemData) {
+
+        // clear away the panels of ships that are gone
+        let mut departed = Vec::new();
+        hud.panels.retain(|panel| {
+            if entities.is_alive(panel.ship) {
+                true
+            } else {
+                departed.extend(panel.entities());
+                false
+            }
+        });
+        for entity in departed {
+            let _ = entities.delete(entity);
+        }
+
+        // and give new ships theirs
+        for (entity, ship) in (&entities, &ships).join() {
+            if hud.panels.iter().any(|panel| panel.ship == entity) {
+                continue;
+            }
+
+            let slot = hud.panels.len();
+            let font = hud.font.clone();
+            let mut storages = HudStorages {
+                entities: &entities,
+                ui_transforms: &mut ui_transforms,
+                ui_images: &mut ui_images,
+                ui_texts: &mut ui_texts,
+            };
+            let panel = create_player_hud(&mut storages, &font, slot, entity, ship.side);
+            hud.panels.push(panel);
+        }
+
+        for panel in hud.panels.iter_mut() {
+            let (combat, energy) = match (combats.get(panel.ship), energies.get(panel.ship)) {
+                (Some(combat), Some(energy)) => (combat, energy),
+                _ => continue,
+            };
+
+            let structure = if combat.max_structure > 0 {
+                combat.structure.max(0) as f32 / combat.max_structure as f32
+            } else {
+                0.0
+            };
+
+            // ships without a shield show an empty track
+            let shield = shields.get(panel.ship).map_or(0.0, |shield| shield.strength / shield.max_strength);
+
+            // full when the weapon is ready to fire again
+            let reload = if combat.time_to_reload > 0.0 {
+                1.0 - combat.reload_timer / combat.time_to_reload
+            } else {
+                1.0
+            };
+
+            set_bar(panel.bar_mut(HudBarKind::Structure), structure, &mut ui_transforms);
+            set_bar(panel.bar_mut(HudBarKind::Shield), shield, &mut ui_transforms);
+            set_bar(panel.bar_mut(HudBarKind::Energy), energy.fraction(), &mut ui_transforms);
+            set_bar(panel.bar_mut(HudBarKind::Reload), reload, &mut ui_transforms);
+
+            let special = match specials.get(panel.ship) {
+                Some(special) if special.is_active() => format!("{} ACTIVE", special.special_type.label()),
+                Some(special) if special.is_ready() => format!("{} READY", special.special_type.label()),
+                Some(special) => format!("{} {:.1}", special.special_type.label(), special.cooldown_timer),
+                None => String::new(),
+            };
+            set_text(panel.special_text, special, &mut ui_texts);
+
+            set_text(panel.score_text, scores.score(panel.side).to_string(), &mut ui_texts);
+        }
+
+        let zone_text = match arena.edge_mode {
+            EdgeMode::DangerZone { delay, max_margin, .. } => {
+                if zone.elapsed < delay {
+                    format!("ZONE CLOSES IN {}", (delay - zone.elapsed).ceil())
+                } else if zone.margin < max_margin {
+                    "ZONE CLOSING".to_string()
+                } else {
+                    String::new()
+                }
+            }
+            _ => String::new(),
+        };
+        set_text(hud.zone_text, zone_text, &mut ui_texts);
+
+        // newest kill at the top, faded a little more with each line down
+        for (line, entity) in hud.kill_feed.iter().enumerate() {
+            let kill = combat_log.kills.iter().rev().nth(line);
+
+            let text = match kill {
+                Some(kill) => match &kill.killer {
+                    Some((killer, _)) => format!("{} [{}] {}", killer, kill.weapon.label(), kill.victim.0),
+                    None => format!("{} [{}]", kill.victim.0, kill.weapon.label()),
+                },
+                None => String::new(),
+            };
+            set_text(*entity, text, &mut ui_texts);
+
+            if let (Some(kill), Some(ui_text)) = (kill, ui_texts.get_mut(*entity)) {
+                let side = kill.killer.as_ref().map_or(kill.victim.1, |(_, side)| *side);
+                let [r, g, b, _] = HudBarKind::Structure.colour(side);
+                ui_text.color = [r, g, b, 1.0 - line as f32 * 0.15];
+            }
+        }
+    }
+}
+
+fn set_bar(bar: &mut HudBar, fraction: f32, ui_transforms: &mut WriteStorage<UiTransform>) {
+    let fraction = fraction.max(0.0).min(1.0);
+
+    if (fraction - bar.shown).abs() < BAR_EPSILON {
+        return;
+    }
+
+    if let Some(transform) = ui_transforms.get_mut(bar.fill) {
+        transform.width = HUD_BAR_WIDTH * fraction;
+        bar.shown = fraction;
+    }
+}
+
+// only touches the text when it has changed, so the glyphs aren't rebuilt every frame
+fn set_text(entity: Entity, value: String, ui_texts: &mut WriteStorage<UiText>) {
+    if let Some(text) = ui_texts.get_mut(entity) {
+        if text.text != value {
+            text.text = value;
+        }
+    }
+}
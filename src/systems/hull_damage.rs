@@ -0,0 +1,104 @@
+use amethyst::{
+    core::{Hidden, Transform, SystemDesc, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::{SpriteRender, palette::Srgba, resources::Tint},
+};
+
+use crate::components::{Combat, HullDamage, Physical, Special, HIT_FLASH_TIME};
+use crate::resources::{SPRITE_PARTICLE, SpriteSheetList};
+use crate::states::RandomGen;
+use super::special::shield_tint;
+
+/// Shows how badly each ship is hurt. The hull swaps to a more battered frame at each damage
+/// tier, smoke and sparks pour off it as it gets worse, and it flashes red on every hit.
+#[derive(SystemDesc)]
+pub struct HullDamageSystem;
+
+impl<'s> System<'s> for HullDamageSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, HullDamage>,
+        ReadStorage<'s, Combat>,
+        ReadStorage<'s, Special>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, Hidden>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        Read<'s, SpriteSheetList>,
+        ReadExpect<'s, RandomGen>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        mut hull_damages,
+        combats,
+        specials,
+        physicals,
+        hiddens,
+        transforms,
+        mut sprite_renders,
+        mut tints,
+        sprite_sheet_list,
+        random_gen,
+        lazy_update,
+        time,
+    ): Self::SystemData) {
+
+        let particle_sheet = sprite_sheet_list.get(SPRITE_PARTICLE).cloned();
+
+        for (entity, hull_damage, combat, physical, transform, sprite_render) in (
+            &entities,
+            &mut hull_damages,
+            &combats,
+            &physicals,
+            &transforms,
+            // a ship whose sprite sheet is missing still flashes, smokes and sparks
+            (&mut sprite_renders).maybe(),
+        ).join() {
+
+            hull_damage.set_tier(combat.structure, combat.max_structure);
+            if let Some(sprite_render) = sprite_render {
+                sprite_render.sprite_number = hull_damage.tier;
+            }
+
+            if combat.structure < hull_damage.last_structure {
+                hull_damage.flash_timer = HIT_FLASH_TIME;
+                tints.insert(entity, Tint(Srgba::new(1.0, 0.3, 0.3, 1.0)))
+                    .expect("Unable to flash damaged ship");
+            } else if hull_damage.flash_timer > 0.0 {
+                // real time, so the flash still clears during a hit-stop
+                hull_damage.flash_timer -= time.delta_real_seconds();
+
+                if hull_damage.flash_timer <= 0.0 {
+                    // hand the ship back to its shield glow if one is still running
+                    let shielding = specials.get(entity).map_or(false, |special| special.is_shielding());
+                    if shielding {
+                        tints.insert(entity, shield_tint()).expect("Unable to tint shielded ship");
+                    } else {
+                        tints.remove(entity);
+                    }
+                }
+            }
+            hull_damage.last_structure = combat.structure;
+
+            // a cloaked ship gives nothing away
+            if hiddens.contains(entity) {
+                continue;
+            }
+
+            if let Some(sprite_sheet) = &particle_sheet {
+                let delta = time.delta_seconds();
+                hull_damage.smoke.emit(
+                    &entities, &lazy_update, sprite_sheet, &random_gen, transform, physical.velocity, delta,
+                );
+                hull_damage.sparks.emit(
+                    &entities, &lazy_update, sprite_sheet, &random_gen, transform, physical.velocity, delta,
+                );
+            }
+        }
+    }
+}
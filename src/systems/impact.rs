@@ -0,0 +1,44 @@
+use amethyst::{
+    core::{SystemDesc, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Read, System, SystemData, World, Write},
+};
+
+use crate::resources::{CombatLog, FeedbackSettings, ScreenShake, EXPLOSION_IMPACT};
+
+// trauma shed per real second
+const SHAKE_DECAY: f32 = 1.5;
+// how far time slows during a hit-stop
+const HIT_STOP_SCALE: f32 = 0.05;
+
+/// Shakes the screen for every ship destroyed this frame, calms the shake and runs hit-stops,
+/// briefly slowing time after a heavy hit.
+/// Both run on real time, so a hit-stop cannot slow its own recovery.
+#[derive(SystemDesc)]
+pub struct ImpactSystem;
+
+impl<'s> System<'s> for ImpactSystem {
+    type SystemData = (
+        Write<'s, ScreenShake>,
+        Read<'s, CombatLog>,
+        Read<'s, FeedbackSettings>,
+        Write<'s, Time>,
+    );
+
+    fn run(&mut self, (mut shake, combat_log, settings, mut time): Self::SystemData) {
+        for _ in combat_log.wrecks.iter() {
+            shake.impact(EXPLOSION_IMPACT);
+        }
+
+        let delta = time.delta_real_seconds();
+
+        shake.trauma = (shake.trauma - SHAKE_DECAY * delta).max(0.0);
+
+        if shake.hit_stop > 0.0 {
+            shake.hit_stop -= delta;
+
+            let stopped = settings.hit_stop && shake.hit_stop > 0.0;
+            time.set_time_scale(if stopped { HIT_STOP_SCALE } else { 1.0 });
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use amethyst::{
+    core::{Hidden, Transform, SystemDesc, math::{Vector2, Vector3}},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::{Camera, SpriteRender, Transparent, palette::Srgba, resources::Tint},
+    ui::{Anchor, UiText, UiTransform},
+};
+
+use crate::components::{Hud, Indicator, Laser, Physical, Ship, Side};
+use crate::resources::{ArenaDefinition, SPRITE_INDICATOR, SpriteSheetList};
+use crate::systems::camera::{VIEW_WIDTH, VIEW_HEIGHT};
+
+// indicator.png is 32 pixels square
+const ARROW_SPRITE_SIZE: f32 = 32.0;
+// size of an arrow on screen, in pixels
+const SHIP_ARROW_SIZE: f32 = 28.0;
+const LASER_ARROW_SIZE: f32 = 16.0;
+// how far inside the edge of the view arrows are drawn, in screen pixels
+const EDGE_INSET: f32 = 24.0;
+// distance labels sit this far further in from their arrow
+const LABEL_INSET: f32 = 30.0;
+// lasers further than this from the ship they are heading for are not worth a warning
+const LASER_WARNING_RANGE: f32 = 1200.0;
+// arrows are drawn over everything in the arena
+const ARROW_Z: f32 = 0.9;
+
+/// Something off screen to point at
+struct Target {
+    dx: f32,
+    dy: f32,
+    distance: f32,
+    size: f32,
+    colour: Srgba,
+}
+
+/// An arrow at the edge of the view and the distance label beside it
+struct Arrow {
+    arrow: Entity,
+    label: Entity,
+}
+
+/// Points arrows from the edge of the screen at ships and incoming lasers out of view.
+/// Arrows follow the shortest way to their target, across a wrapping edge if that is nearer,
+/// and are labelled with the distance to the ship the target is closing on.
+/// Arrows are pooled, and the spares hidden, so they aren't rebuilt every frame.
+#[derive(SystemDesc, Default)]
+pub struct IndicatorSystem {
+    arrows: Vec<Arrow>,
+}
+
+impl<'s> System<'s> for IndicatorSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, Camera>,
+        WriteStorage<'s, Indicator>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Transparent>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, SpriteSheetList>,
+        ReadExpect<'s, Hud>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        lasers,
+        physicals,
+        cameras,
+        mut indicators,
+        mut transforms,
+        mut sprite_renders,
+        mut tints,
+        mut transparents,
+        mut hiddens,
+        mut ui_transforms,
+        mut ui_texts,
+        arena,
+        sprite_sheet_list,
+        hud,
+    ): Self::SystemData) {
+
+        // arrows are cleared away with the rest of a match
+        self.arrows.retain(|arrow| entities.is_alive(arrow.arrow) && entities.is_alive(arrow.label));
+
+        let sprite_sheet = match sprite_sheet_list.get(SPRITE_INDICATOR) {
+            Some(sprite_sheet) => sprite_sheet.clone(),
+            None => return,
+        };
+
+        let (camera_x, camera_y, zoom) = match (&cameras, &transforms).join().next() {
+            Some((_, transform)) => (transform.translation().x, transform.translation().y, transform.scale().x),
+            None => return,
+        };
+
+        let arena_state = arena.state();
+        let half_width = VIEW_WIDTH * zoom / 2.0;
+        let half_height = VIEW_HEIGHT * zoom / 2.0;
+        let off_screen = |dx: f32, dy: f32| dx.abs() > half_width || dy.abs() > half_height;
+
+        let positions: Vec<(Side, f32, f32)> = (&ships, &transforms).join()
+            .map(|(ship, transform)| (ship.side, transform.translation().x, transform.translation().y))
+            .collect();
+
+        // nearest ship from another side to (x, y), as the distance and the offset to it
+        let nearest_enemy = |side: Side, x: f32, y: f32| -> Option<(f32, f32, f32)> {
+            positions.iter()
+                .filter(|(other, _, _)| *other != side)
+                .map(|(_, ex, ey)| {
+                    let (dx, dy) = arena_state.delta(x, y, *ex, *ey);
+                    (dx.hypot(dy), dx, dy)
+                })
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+        };
+
+        let mut targets: Vec<Target> = Vec::new();
+
+        for (side, x, y) in positions.iter() {
+            let (dx, dy) = arena_state.delta(camera_x, camera_y, *x, *y);
+            if !off_screen(dx, dy) {
+                continue;
+            }
+
+            let distance = nearest_enemy(*side, *x, *y).map_or(0.0, |(distance, _, _)| distance);
+            targets.push(Target { dx, dy, distance, size: SHIP_ARROW_SIZE, colour: side_colour(*side, 1.0) });
+        }
+
+        for (laser, transform, physical) in (&lasers, &transforms, &physicals).join() {
+            let (x, y) = (transform.translation().x, transform.translation().y);
+            let (dx, dy) = arena_state.delta(camera_x, camera_y, x, y);
+            if !off_screen(dx, dy) {
+                continue;
+            }
+
+            // only lasers closing on a ship they can hurt
+            if let Some((distance, ex, ey)) = nearest_enemy(laser.side, x, y) {
+                let closing = physical.velocity.dot(&Vector2::new(ex, ey)) > 0.0;
+                if closing && distance < LASER_WARNING_RANGE {
+                    targets.push(Target { dx, dy, distance, size: LASER_ARROW_SIZE, colour: side_colour(laser.side, 0.8) });
+                }
+            }
+        }
+
+        while self.arrows.len() < targets.len() {
+            let arrow = entities.create();
+            indicators.insert(arrow, Indicator).expect("Unable to create indicator");
+            transforms.insert(arrow, Transform::default()).expect("Unable to place indicator");
+            sprite_renders.insert(arrow, SpriteRender { sprite_sheet: sprite_sheet.clone(), sprite_number: 0 })
+                .expect("Unable to draw indicator");
+            transparents.insert(arrow, Transparent).expect("Unable to draw indicator");
+
+            let label = entities.create();
+            ui_transforms.insert(label, UiTransform::new(
+                "IndicatorLabel".to_string(), Anchor::Middle, Anchor::Middle,
+                0.0, 0.0, 1.0, 120.0, 20.0,
+            )).expect("Unable to place indicator label");
+            ui_texts.insert(label, UiText::new(hud.font.clone(), "".to_string(), [1.0, 1.0, 1.0, 1.0], 16.0))
+                .expect("Unable to write indicator label");
+
+            self.arrows.push(Arrow { arrow, label });
+        }
+
+        let inset_width = half_width - EDGE_INSET * zoom;
+        let inset_height = half_height - EDGE_INSET * zoom;
+
+        for (index, arrow) in self.arrows.iter().enumerate() {
+            let target = match targets.get(index) {
+                Some(target) => target,
+                None => {
+                    hiddens.insert(arrow.arrow, Hidden).expect("Unable to hide indicator");
+                    hiddens.insert(arrow.label, Hidden).expect("Unable to hide indicator");
+                    continue;
+                }
+            };
+            hiddens.remove(arrow.arrow);
+            hiddens.remove(arrow.label);
+
+            // pull the target in along its bearing until it sits on the inset edge of the view
+            let edge = (inset_width / target.dx.abs()).min(inset_height / target.dy.abs());
+            let (ax, ay) = (target.dx * edge, target.dy * edge);
+            let heading = (-target.dx).atan2(target.dy);
+
+            if let Some(transform) = transforms.get_mut(arrow.arrow) {
+                let scale = target.size / ARROW_SPRITE_SIZE * zoom;
+                transform.set_translation_xyz(camera_x + ax, camera_y + ay, ARROW_Z);
+                transform.set_rotation_2d(heading);
+                transform.set_scale(Vector3::new(scale, scale, 1.0));
+            }
+            tints.insert(arrow.arrow, Tint(target.colour)).expect("Unable to tint indicator");
+
+            // labels are on screen, in pixels from the centre of the window
+            let length = ax.hypot(ay) / zoom;
+            let label_scale = ((length - LABEL_INSET) / length).max(0.0) / zoom;
+            if let Some(ui_transform) = ui_transforms.get_mut(arrow.label) {
+                ui_transform.local_x = ax * label_scale;
+                ui_transform.local_y = ay * label_scale;
+            }
+            if let Some(text) = ui_texts.get_mut(arrow.label) {
+                text.text = format!("{:.0}", target.distance);
+                text.color = [target.colour.red, target.colour.green, target.colour.blue, 1.0];
+            }
+        }
+    }
+}
+
+fn side_colour(side: Side, alpha: f32) -> Srgba {
+    match side {
+        Side::Light => Srgba::new(0.3, 1.0, 0.4, alpha),
+        Side::Dark => Srgba::new(1.0, 0.35, 0.25, alpha),
+    }
+}
@@ -1,24 +1,41 @@
 use amethyst::{
-    ecs::{Join, Read, System, WriteStorage},
+    core::SystemDesc,
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
     input::{InputHandler, StringBindings},
 };
 
-use crate::components::Ship;
+use crate::components::{Ship, Side, Pilot, ShipControl};
 
+/// Turns keyboard input into ShipControl commands for human piloted ships
+#[derive(SystemDesc)]
 pub struct InputSystem;
 
-impl<'s> System for InputSystem {
+impl<'s> System<'s> for InputSystem {
     type SystemData = (
-        WriteStorage<'s, Ship>
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Pilot>,
+        WriteStorage<'s, ShipControl>,
         Read<'s, InputHandler<StringBindings>>,
     );
 
-    fn run(&mut self, data: Self::SystemData) {
-        let (mut ships, input) = data;
+    fn run(&mut self, (ships, pilots, mut controls, input): Self::SystemData) {
 
-        for ship in &mut ships {
-            
+        for (ship, pilot, control) in (&ships, &pilots, &mut controls).join() {
+            if *pilot != Pilot::Human {
+                continue;
+            }
+
+            let (rotate, accelerate, shoot, boost, special) = match ship.side {
+                Side::Light => ("light_rotate", "light_accelerate", "light_shoot", "light_boost", "light_special"),
+                Side::Dark => ("dark_rotate", "dark_accelerate", "dark_shoot", "dark_boost", "dark_special"),
+            };
+
+            control.rotate = input.axis_value(rotate).unwrap_or(0.0);
+            control.thrust = input.axis_value(accelerate).unwrap_or(0.0);
+            control.fire = input.action_is_down(shoot).unwrap_or(false);
+            control.boost = input.action_is_down(boost).unwrap_or(false);
+            control.special = input.action_is_down(special).unwrap_or(false);
         }
     }
-
-}
\ No newline at end of file
+}
@@ -6,19 +6,18 @@ use amethyst::{
     core::math::{Vector3, Vector2, UnitQuaternion, Translation3},
     core::SystemDesc,
     derive::SystemDesc,
-    renderer::{transparent::Transparent, SpriteRender},
-    input::{InputHandler, StringBindings},
+    renderer::transparent::Transparent,
     ecs::prelude::{Entity, Join, Read, ReadExpect, Entities, ReadStorage, System, SystemData, World, WriteStorage, LazyUpdate},
 };
 
 use std::ops::Deref;
 use smallvec::SmallVec;
 
-use crate::resources::{SpriteSheetList, AssetType};
+use crate::resources::SpriteSheetList;
 use crate::audio::{play_laser_sound, Sounds};
-use crate::components::{LaserImpact, Laser, Ship, Side};
+use crate::components::{LaserImpact, Laser, Ship, ShipControl};
 use crate::components::{Animation, AnimationId, AnimationPrefabData};
-use crate::components::{Physical, Combat, LaserType};
+use crate::components::{Physical, Combat, LaserType, Energy, ParticleEmitter, LIGHT_LASER_TRAIL, DARK_LASER_TRAIL};
 
 #[derive(SystemDesc)]
 pub struct LaserSystem;
@@ -28,10 +27,11 @@ impl<'s> System<'s> for LaserSystem {
     type SystemData = (
         Entities<'s>,
         WriteStorage<'s, Laser>,
-        Read<'s, InputHandler<StringBindings>>,
+        ReadStorage<'s, ShipControl>,
         Read<'s, SpriteSheetList>,
         ReadStorage<'s, Ship>,
         WriteStorage<'s, Combat>,
+        WriteStorage<'s, Energy>,
         WriteStorage<'s, Transform>,
 
         Read<'s, AssetStorage<Source>>,
@@ -45,10 +45,11 @@ impl<'s> System<'s> for LaserSystem {
     fn run(&mut self, (
         entities, 
         mut lasers, 
-        input,
+        controls,
         sprite_sheet_list,
         ships, 
         mut combats, 
+        mut energies,
         mut transforms,
         storage,
         sounds,
@@ -56,17 +57,15 @@ impl<'s> System<'s> for LaserSystem {
         lazy, 
         time): Self::SystemData) {
 
-        for (ship, transform, combat) in (&ships, &mut transforms, &mut combats).join() {
+        for (ship, control, transform, combat, energy) in (&ships, &controls, &mut transforms, &mut combats, &mut energies).join() {
             // does ship shoot?
-            let shoot = match ship.side {
-                Side::Light => input.action_is_down("light_shoot").unwrap_or(false),
-                Side::Dark => input.action_is_down("dark_shoot").unwrap_or(false),
-            };
+            let shoot = control.fire;
 
             let mut new_lasers = SmallVec::<[NewLaser; 8]>::new();
 
             if combat.reload_timer <= 0.0 {
-                if shoot {
+                // every shot draws from the ship's battery
+                if shoot && energy.try_consume(energy.laser_cost) {
 
                     combat.reload_timer = combat.time_to_reload;
     
@@ -102,6 +101,10 @@ impl<'s> System<'s> for LaserSystem {
                 play_laser_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
             }
 
+            // the ship's laser sprite, or the placeholder if it is missing; without either
+            // the lasers still fly, just unseen
+            let laser_sprite_render = sprite_sheet_list.render(&ship.laser_sprite_sheet);
+
             for new_laser in new_lasers {
 
                 match combat.laser_type {
@@ -110,20 +113,15 @@ impl<'s> System<'s> for LaserSystem {
         
                         let laser = Laser::new(combat.laser_timer, combat.laser_damage, ship.side);
 
-                        let light_laser_sprite_sheet_handle = sprite_sheet_list.get(AssetType::LaserLight).unwrap();
-
-                         // Construct sprite render for light_laser
-                        let light_laser_sprite_render = SpriteRender {
-                            sprite_sheet: light_laser_sprite_sheet_handle.clone(),
-                            sprite_number: 0,
-                        };
-            
                         let e = entities.create();
         
                         lazy.insert(e, laser);
                         lazy.insert(e, physical);
                         lazy.insert(e, laser_t);
-                        lazy.insert(e, light_laser_sprite_render.clone());
+                        if let Some(laser_sprite_render) = laser_sprite_render.as_ref() {
+                            lazy.insert(e, laser_sprite_render.clone());
+                        }
+                        lazy.insert(e, ParticleEmitter::laser_trail(LIGHT_LASER_TRAIL));
                     }
                     LaserType::Dual => {
                         // Laser 1
@@ -134,20 +132,15 @@ impl<'s> System<'s> for LaserSystem {
 
                         let laser = Laser::new(combat.laser_timer, combat.laser_damage, ship.side);
 
-                        let dark_laser_sprite_sheet_handle = sprite_sheet_list.get(AssetType::LaserDark).unwrap();
-
-                        // Construct sprite render for thruster
-                        let dark_laser_sprite_render = SpriteRender {
-                            sprite_sheet: dark_laser_sprite_sheet_handle.clone(),
-                            sprite_number: 0,
-                        };
-            
                         let e = entities.create();
         
                         lazy.insert(e, laser.clone());
                         lazy.insert(e, physical);
                         lazy.insert(e, laser_t.clone());
-                        lazy.insert(e, dark_laser_sprite_render.clone());
+                        if let Some(laser_sprite_render) = laser_sprite_render.as_ref() {
+                            lazy.insert(e, laser_sprite_render.clone());
+                        }
+                        lazy.insert(e, ParticleEmitter::laser_trail(DARK_LASER_TRAIL));
 
                         // Laser 2
 
@@ -158,7 +151,10 @@ impl<'s> System<'s> for LaserSystem {
                         lazy.insert(f, laser.clone());
                         lazy.insert(f, physical);
                         lazy.insert(f, laser_t.clone());
-                        lazy.insert(f, dark_laser_sprite_render.clone());
+                        if let Some(laser_sprite_render) = laser_sprite_render.as_ref() {
+                            lazy.insert(f, laser_sprite_render.clone());
+                        }
+                        lazy.insert(f, ParticleEmitter::laser_trail(DARK_LASER_TRAIL));
                     }
                 }
 
@@ -174,9 +170,6 @@ impl<'s> System<'s> for LaserSystem {
             if laser.timer <= 0.0 {
                 // time up, remove laser
                 entities.delete(entity).expect("Couldn't delete entity");
-            } else {
-                // update timer
-                laser.timer -= time.delta_seconds();
             }
         }
 
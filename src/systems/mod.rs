@@ -4,10 +4,44 @@ pub use self::collision_system::CollisionSystem;
 pub use self::physics::PhysicsSystem;
 pub use self::winner::WinnerSystem;
 pub use self::animation::{AnimationControlSystem, LaserImpactAnimationSystem};
+pub use self::input::InputSystem;
+pub use self::ai_pilot::AiPilotSystem;
+pub use self::energy::EnergySystem;
+pub use self::special::SpecialSystem;
+pub use self::shield::ShieldSystem;
+pub use self::asteroid::AsteroidSystem;
+pub use self::arena_edge::ArenaEdgeSystem;
+pub use self::camera::CameraSystem;
+pub use self::ghost::GhostSystem;
+pub use self::impact::ImpactSystem;
+pub use self::starfield::StarfieldSystem;
+pub use self::particle::ParticleSystem;
+pub use self::hull_damage::HullDamageSystem;
+pub use self::hud::HudSystem;
+pub use self::indicator::IndicatorSystem;
+pub use self::radar::RadarSystem;
+pub use self::floating_text::FloatingTextSystem;
 
-mod ship_movement;
+pub mod ship_movement;
 mod laser;
 mod collision_system;
-mod physics;
+pub mod physics;
 mod winner;
-pub mod animation;
\ No newline at end of file
+pub mod animation;
+mod input;
+pub mod ai_pilot;
+mod energy;
+pub mod special;
+mod shield;
+mod asteroid;
+pub mod arena_edge;
+pub mod camera;
+mod ghost;
+mod impact;
+mod starfield;
+mod particle;
+mod hull_damage;
+mod hud;
+mod indicator;
+mod radar;
+mod floating_text;
\ No newline at end of file
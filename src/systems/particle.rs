@@ -0,0 +1,104 @@
+use amethyst::{
+    core::{Hidden, Transform, SystemDesc, Time, math::{Vector2, Vector3}},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::resources::Tint,
+};
+
+use crate::components::{Particle, ParticleEmitter, Physical, WRECKAGE, spawn_burst};
+use crate::resources::{SPRITE_PARTICLE, CombatLog, SpriteSheetList};
+use crate::states::RandomGen;
+
+// pieces of burning wreckage left by a destroyed ship
+const WRECKAGE_COUNT: usize = 40;
+
+/// Streams particles from every emitter and bursts wreckage from every ship destroyed this frame,
+/// then moves, fades and shrinks them until they burn out.
+/// Particles move per frame like Physical bodies, scaled by time so a hit-stop slows them too.
+#[derive(SystemDesc)]
+pub struct ParticleSystem;
+
+impl<'s> System<'s> for ParticleSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, ParticleEmitter>,
+        WriteStorage<'s, Particle>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, Hidden>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        Read<'s, CombatLog>,
+        Read<'s, SpriteSheetList>,
+        ReadExpect<'s, RandomGen>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        mut emitters,
+        mut particles,
+        physicals,
+        hiddens,
+        mut transforms,
+        mut tints,
+        combat_log,
+        sprite_sheet_list,
+        random_gen,
+        lazy_update,
+        time,
+    ): Self::SystemData) {
+
+        let sprite_sheet = match sprite_sheet_list.get(SPRITE_PARTICLE) {
+            Some(sprite_sheet) => sprite_sheet.clone(),
+            None => return,
+        };
+
+        // emit, except from cloaked ships
+        for (emitter, transform, physical, _) in (&mut emitters, &transforms, physicals.maybe(), !&hiddens).join() {
+            let velocity = physical.map_or(Vector2::new(0.0, 0.0), |physical| physical.velocity);
+
+            emitter.emit(
+                &entities,
+                &lazy_update,
+                &sprite_sheet,
+                &random_gen,
+                transform,
+                velocity,
+                time.delta_seconds(),
+            );
+        }
+
+        // leave wreckage where ships blew up
+        for wreck in combat_log.wrecks.iter() {
+            spawn_burst(
+                &entities,
+                &lazy_update,
+                sprite_sheet.clone(),
+                &random_gen,
+                &WRECKAGE,
+                WRECKAGE_COUNT,
+                wreck.x,
+                wreck.y,
+                wreck.velocity,
+            );
+        }
+
+        // age
+        for (entity, particle, transform, tint) in (&entities, &mut particles, &mut transforms, &mut tints).join() {
+            particle.age += time.delta_seconds();
+
+            if particle.age >= particle.style.lifetime {
+                let _ = entities.delete(entity);
+                continue;
+            }
+
+            transform.prepend_translation_x(particle.velocity[0] * time.time_scale());
+            transform.prepend_translation_y(particle.velocity[1] * time.time_scale());
+
+            let scale = particle.scale();
+            transform.set_scale(Vector3::new(scale, scale, 1.0));
+            tint.0 = particle.colour();
+        }
+    }
+}
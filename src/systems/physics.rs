@@ -1,9 +1,12 @@
-use amethyst::core::{Transform, SystemDesc, Time};
+use amethyst::core::{Transform, SystemDesc, Time, math::Vector2};
 use amethyst::derive::SystemDesc;
 use amethyst::ecs::{Join, ReadStorage, System, SystemData, World, WriteStorage, Read};
 
-use crate::states::{ARENA_HEIGHT, ARENA_WIDTH};
-use crate::components::Physical;
+use crate::components::{Physical, GravityWell};
+use crate::resources::{ArenaDefinition, ArenaState};
+
+/// Gravitational constant for gravity wells
+pub const GRAVITY: f32 = 1.0;
 
 #[derive(SystemDesc)]
 pub struct PhysicsSystem;
@@ -11,48 +14,132 @@ pub struct PhysicsSystem;
 impl<'s> System<'s> for PhysicsSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
-        ReadStorage<'s, Physical>,
+        WriteStorage<'s, Physical>,
+        ReadStorage<'s, GravityWell>,
+        Read<'s, ArenaDefinition>,
         Read<'s, Time>,
     );
 
-    fn run(&mut self, (mut transforms, physicals, _time): Self::SystemData) {
-        for (transform, physical) in (&mut transforms, &physicals).join() {
+    fn run(&mut self, (mut transforms, mut physicals, gravity_wells, arena, time): Self::SystemData) {
+
+        let arena_state = arena.state();
 
-            transform.prepend_translation_x(physical.velocity[0]);
-            transform.prepend_translation_y(physical.velocity[1]);
+        let wells: Vec<(f32, f32, GravityWell)> = (&transforms, &gravity_wells).join()
+            .map(|(transform, well)| (transform.translation().x, transform.translation().y, *well))
+            .collect();
 
+        for (transform, physical) in (&mut transforms, &mut physicals).join() {
+
+            // inverse-square pull toward every gravity well
+            for (well_x, well_y, well) in wells.iter() {
+                let pull = gravity_acceleration(
+                    &arena_state,
+                    transform.translation().x,
+                    transform.translation().y,
+                    *well_x,
+                    *well_y,
+                    well,
+                );
+                physical.velocity += pull * time.delta_seconds();
+            }
+
+            // velocity is per frame, scaled so a hit-stop slows everything down
+            transform.prepend_translation_x(physical.velocity[0] * time.time_scale());
+            transform.prepend_translation_y(physical.velocity[1] * time.time_scale());
+
+
+            // wrap arena, walls are handled by the ArenaEdgeSystem
+            if !arena.wraps() {
+                continue;
+            }
 
-            // wrap arena
             let physical_x = transform.translation().x;
             let physical_y = transform.translation().y;
 
             // top edge
             if (physical_y <= physical.radius && physical.velocity[1] < 0.0)
-            || (physical_y >= ARENA_HEIGHT - physical.radius && physical.velocity[1] > 0.0)
+            || (physical_y >= arena.height - physical.radius && physical.velocity[1] > 0.0)
             {
                 transform.translation_mut().y = 0.0 - physical.radius;
             }
 
             // bottom edge
-            if (physical_y >= physical.radius + ARENA_HEIGHT && physical.velocity[1] > 0.0)
+            if (physical_y >= physical.radius + arena.height && physical.velocity[1] > 0.0)
             || (physical_y <= physical.radius && physical.velocity[1] < 0.0)
             {
-                transform.translation_mut().y = ARENA_HEIGHT + physical.radius;
+                transform.translation_mut().y = arena.height + physical.radius;
             }
 
             // left edge
             if (physical_x <= physical.radius && physical.velocity[0] < 0.0)
-            || (physical_x >= ARENA_WIDTH - physical.radius && physical.velocity[0] > 0.0)
+            || (physical_x >= arena.width - physical.radius && physical.velocity[0] > 0.0)
             {
                 transform.translation_mut().x = 0.0 - physical.radius;
             }
 
             // right edge
-            if (physical_x >= physical.radius + ARENA_WIDTH && physical.velocity[0] > 0.0)
+            if (physical_x >= physical.radius + arena.width && physical.velocity[0] > 0.0)
             || (physical_x <= physical.radius && physical.velocity[0] < 0.0)
             {
-                transform.translation_mut().x = ARENA_WIDTH + physical.radius;
+                transform.translation_mut().x = arena.width + physical.radius;
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Acceleration a gravity well applies at (x, y), across the shortest wrapped distance.
+/// The force on a body scales with its mass, so ships and lasers fall at the same rate.
+/// Distance is clamped to the well's surface so nothing is flung out of the centre.
+fn gravity_acceleration(arena: &ArenaState, x: f32, y: f32, well_x: f32, well_y: f32, well: &GravityWell) -> Vector2<f32> {
+    let (dx, dy) = arena.delta(x, y, well_x, well_y);
+    let delta = Vector2::new(dx, dy);
+
+    if delta.magnitude() == 0.0 {
+        return Vector2::new(0.0, 0.0);
+    }
+
+    let distance = delta.magnitude().max(well.radius);
+
+    delta.normalize() * (GRAVITY * well.mass / (distance * distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WELL: GravityWell = GravityWell { mass: 40000.0, radius: 50.0, damage: 20 };
+
+    fn arena() -> ArenaState {
+        ArenaState { width: 1000.0, height: 800.0, wraps: true, margin: 0.0 }
+    }
+
+    #[test]
+    fn gravity_falls_off_with_the_square_of_distance() {
+        let near = gravity_acceleration(&arena(), 400.0, 400.0, 500.0, 400.0, &WELL);
+        let far = gravity_acceleration(&arena(), 300.0, 400.0, 500.0, 400.0, &WELL);
+
+        // pulled toward the well, four times harder at half the distance
+        assert!((near[0] - 4.0).abs() < 1e-4);
+        assert!(near[1].abs() < 1e-4);
+        assert!((near[0] / far[0] - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn gravity_is_clamped_inside_the_well() {
+        let surface = gravity_acceleration(&arena(), 450.0, 400.0, 500.0, 400.0, &WELL);
+        let inside = gravity_acceleration(&arena(), 490.0, 400.0, 500.0, 400.0, &WELL);
+        let centre = gravity_acceleration(&arena(), 500.0, 400.0, 500.0, 400.0, &WELL);
+
+        assert!((surface - inside).magnitude() < 1e-4);
+        assert!(centre.magnitude() < f32::EPSILON);
+    }
+
+    #[test]
+    fn gravity_pulls_across_a_wrapping_edge() {
+        let pull = gravity_acceleration(&arena(), 950.0, 400.0, 50.0, 400.0, &WELL);
+
+        // the well is 100 away through the right edge, not 900 back to the left
+        assert!(pull[0] > 0.0);
+        assert!((pull[0] - 4.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,171 @@
+use amethyst::{
+    core::{Hidden, Transform, SystemDesc},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, SystemData, World, WriteExpect, WriteStorage},
+    renderer::Camera,
+    ui::{Anchor, UiImage, UiTransform},
+};
+
+use crate::components::{Asteroid, GravityWell, Laser, Physical, Radar, Ship, Side, RADAR_MARGIN};
+use crate::resources::ArenaDefinition;
+use crate::systems::camera::{VIEW_WIDTH, VIEW_HEIGHT};
+
+// blip sizes on the radar, in screen pixels
+const SHIP_BLIP: f32 = 6.0;
+const LASER_BLIP: f32 = 2.0;
+const MIN_BODY_BLIP: f32 = 3.0;
+
+/// Something to mark on the radar, in arena coordinates
+struct Blip {
+    x: f32,
+    y: f32,
+    size: f32,
+    colour: [f32; 4],
+}
+
+/// Draws the whole arena in miniature on the radar: ships, lasers, asteroids and planets,
+/// with a shaded box for the part the camera can see
+#[derive(SystemDesc)]
+pub struct RadarSystem;
+
+impl<'s> System<'s> for RadarSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Asteroid>,
+        ReadStorage<'s, GravityWell>,
+        ReadStorage<'s, Physical>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, Hidden>,
+        WriteExpect<'s, Radar>,
+        Read<'s, ArenaDefinition>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        lasers,
+        asteroids,
+        gravity_wells,
+        physicals,
+        cameras,
+        transforms,
+        mut ui_transforms,
+        mut ui_images,
+        mut hiddens,
+        mut radar,
+        arena,
+    ): Self::SystemData) {
+
+        if !radar.visible {
+            for entity in radar.entities() {
+                let _ = hiddens.insert(entity, Hidden);
+            }
+            return;
+        }
+        let _ = hiddens.remove(radar.frame);
+
+        // arena pixels per radar pixel
+        let scale = radar.width / arena.width;
+
+        let mut blips: Vec<Blip> = Vec::new();
+
+        for (gravity_well, transform) in (&gravity_wells, &transforms).join() {
+            blips.push(Blip {
+                x: transform.translation().x,
+                y: transform.translation().y,
+                size: (gravity_well.radius * 2.0 * scale).max(MIN_BODY_BLIP),
+                colour: [0.7, 0.5, 0.35, 1.0],
+            });
+        }
+
+        for (_, physical, transform) in (&asteroids, &physicals, &transforms).join() {
+            blips.push(Blip {
+                x: transform.translation().x,
+                y: transform.translation().y,
+                size: (physical.radius * 2.0 * scale).max(MIN_BODY_BLIP),
+                colour: [0.55, 0.55, 0.55, 1.0],
+            });
+        }
+
+        for (laser, transform) in (&lasers, &transforms).join() {
+            blips.push(Blip {
+                x: transform.translation().x,
+                y: transform.translation().y,
+                size: LASER_BLIP,
+                colour: side_colour(laser.side),
+            });
+        }
+
+        for (ship, transform) in (&ships, &transforms).join() {
+            blips.push(Blip {
+                x: transform.translation().x,
+                y: transform.translation().y,
+                size: SHIP_BLIP,
+                colour: side_colour(ship.side),
+            });
+        }
+
+        // radar positions are offsets from the window's top right corner to the blip's centre
+        let (left, bottom) = (-RADAR_MARGIN - radar.width, -RADAR_MARGIN - radar.height);
+
+        if let Some((_, camera)) = (&cameras, &transforms).join().next() {
+            let zoom = camera.scale().x;
+            let centre_x = camera.translation().x * scale;
+            let centre_y = camera.translation().y * scale;
+            let half_width = VIEW_WIDTH * zoom * scale / 2.0;
+            let half_height = VIEW_HEIGHT * zoom * scale / 2.0;
+
+            // clipped to the radar, so a view across a wrapping edge shows the side it is mostly on
+            let (x0, x1) = ((centre_x - half_width).max(0.0), (centre_x + half_width).min(radar.width));
+            let (y0, y1) = ((centre_y - half_height).max(0.0), (centre_y + half_height).min(radar.height));
+
+            if let Some(view) = ui_transforms.get_mut(radar.view) {
+                view.local_x = left + (x0 + x1) / 2.0;
+                view.local_y = bottom + (y0 + y1) / 2.0;
+                view.width = (x1 - x0).max(0.0);
+                view.height = (y1 - y0).max(0.0);
+            }
+            let _ = hiddens.remove(radar.view);
+        }
+
+        while radar.blips.len() < blips.len() {
+            let blip = entities.create();
+            ui_transforms.insert(blip, UiTransform::new(
+                "RadarBlip".to_string(), Anchor::TopRight, Anchor::Middle,
+                0.0, 0.0, 3.0, 0.0, 0.0,
+            )).expect("Unable to place radar blip");
+            radar.blips.push(blip);
+        }
+
+        for (index, entity) in radar.blips.iter().enumerate() {
+            let blip = match blips.get(index) {
+                Some(blip) => blip,
+                None => {
+                    let _ = hiddens.insert(*entity, Hidden);
+                    continue;
+                }
+            };
+            let _ = hiddens.remove(*entity);
+
+            if let Some(ui_transform) = ui_transforms.get_mut(*entity) {
+                ui_transform.local_x = left + blip.x * scale;
+                ui_transform.local_y = bottom + blip.y * scale;
+                ui_transform.width = blip.size;
+                ui_transform.height = blip.size;
+            }
+            ui_images.insert(*entity, UiImage::SolidColor(blip.colour)).expect("Unable to colour radar blip");
+        }
+    }
+}
+
+fn side_colour(side: Side) -> [f32; 4] {
+    match side {
+        Side::Light => [0.3, 1.0, 0.4, 1.0],
+        Side::Dark => [1.0, 0.35, 0.25, 1.0],
+    }
+}
@@ -0,0 +1,53 @@
+use amethyst::{
+    core::{Hidden, Parent, SystemDesc, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::{palette::Srgba, resources::Tint},
+};
+
+use crate::components::{Shield, ShieldShimmer, SHIMMER_TIME};
+
+// how fast the shimmer flickers, in radians per second
+const SHIMMER_FREQUENCY: f32 = 40.0;
+
+/// Regenerates shields and flickers the shimmer around ships whose shields were hit
+#[derive(SystemDesc)]
+pub struct ShieldSystem;
+
+impl<'s> System<'s> for ShieldSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Shield>,
+        ReadStorage<'s, ShieldShimmer>,
+        ReadStorage<'s, Parent>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Tint>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (entities, mut shields, shimmers, parents, mut hidden, mut tints, time): Self::SystemData) {
+        for shield in (&mut shields).join() {
+            shield.regenerate(time.delta_seconds());
+        }
+
+        for (entity, _, parent) in (&entities, &shimmers, &parents).join() {
+            let shimmer_timer = match shields.get(parent.entity) {
+                Some(shield) => shield.shimmer_timer,
+                None => continue,
+            };
+
+            if shimmer_timer > 0.0 {
+                hidden.remove(entity);
+
+                // fade out over the shimmer time while flickering
+                let flicker = 0.5 + 0.5 * (time.absolute_time_seconds() as f32 * SHIMMER_FREQUENCY).sin();
+                let alpha = 0.6 * (shimmer_timer / SHIMMER_TIME) * flicker;
+
+                tints.insert(entity, Tint(Srgba::new(0.4, 0.8, 1.0, alpha)))
+                    .expect("Unable to tint shield shimmer");
+            } else if !hidden.contains(entity) {
+                hidden.insert(entity, Hidden).expect("Unable to hide shield shimmer");
+            }
+        }
+    }
+}
@@ -5,15 +5,17 @@ use amethyst::{
     assets::AssetStorage,
     audio::{output::Output, Source},
 };
-use amethyst::ecs::{Join, Entities, LazyUpdate, Read, ReadExpect, System, SystemData, World, WriteStorage};
-use amethyst::input::{InputHandler, StringBindings};
+use amethyst::ecs::{Join, Entities, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage};
 
 use crate::audio::{play_thrust_sound, Sounds};
-use crate::components::{Ship, Side, Physical};
-use crate::components::{Thrust};
+use crate::components::{Ship, Physical, ShipControl, Energy};
+use crate::components::{Thrust, ParticleEmitter};
 
 use std::ops::Deref;
 
+const BOOST_ACCELERATION: f32 = 2.0;
+const BOOST_MAX_VELOCITY: f32 = 1.5;
+
 #[derive(SystemDesc)]
 pub struct MovementSystem;
 
@@ -23,11 +25,13 @@ impl<'s> System<'s> for MovementSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Physical>,
         WriteStorage<'s, Ship>,
-        Read<'s, InputHandler<StringBindings>>,
+        ReadStorage<'s, ShipControl>,
+        WriteStorage<'s, Energy>,
         Read<'s, Time>,
         WriteStorage<'s, Thrust>,
         WriteStorage<'s, Hidden>,
         WriteStorage<'s, Parent>,
+        WriteStorage<'s, ParticleEmitter>,
 
         Read<'s, AssetStorage<Source>>,
         ReadExpect<'s, Sounds>,
@@ -40,11 +44,13 @@ impl<'s> System<'s> for MovementSystem {
         mut transforms, 
         mut physicals, 
         mut ships,
-        input, 
+        controls,
+        mut energies,
         time,
         mut thrust_entities,
         mut hidden_entities,
         mut parents,
+        mut emitters,
 
         // audio
         storage,
@@ -53,64 +59,80 @@ impl<'s> System<'s> for MovementSystem {
         lazy,
      ): Self::SystemData) {
          
-        for (entity, ship, transform, physical) in (&entities, &mut ships, &mut transforms, &mut physicals).join() {
-
-            let movement = match ship.side {
-                Side::Light => input.axis_value("light_rotate"),
-                Side::Dark => input.axis_value("dark_rotate"),
-            };
-            if let Some(mv_amount) = movement {
-                if mv_amount != 0.0 {
-                    let scaled_amount = physical.agility * mv_amount as f32;
-                    transform.rotate_2d(scaled_amount);
-                }
-            }
-
-            let thrust = match ship.side {
-                Side::Light => input.axis_value("light_accelerate"),
-                Side::Dark => input.axis_value("dark_accelerate"),
-            };
+        for (entity, ship, control, energy, transform, physical, emitter) in (&entities, &mut ships, &controls, &mut energies, &mut transforms, &mut physicals, (&mut emitters).maybe()).join() {
 
-            if let Some(thrust) = thrust {
+            let mv_amount = control.rotate;
+            if mv_amount != 0.0 {
+                let scaled_amount = physical.agility * mv_amount as f32;
+                transform.rotate_2d(scaled_amount);
+            }
 
-                // Get thrust entity so we can apply and remove the Hidden tag
-                let thrust_entity = (&entities, &parents).join()
-                    .find_map(|(ent, parent)| if parent.entity == entity {
-                    Some(ent)
+            let thrust = control.thrust;
+            // a cloaked ship keeps its exhaust hidden too
+            let cloaked = hidden_entities.contains(entity);
+
+            // Get thrust entity so we can apply and remove the Hidden tag
+            let thrust_entity = (&entities, &parents, &thrust_entities).join()
+                .find_map(|(ent, parent, _)| if parent.entity == entity {
+                Some(ent)
+            } else {
+                None
+            });
+
+            if thrust > 0.0 {
+                // boosting doubles acceleration and raises top speed while energy lasts
+                let boosted = control.boost
+                    && energy.try_consume(energy.boost_cost * time.delta_seconds());
+                let (acceleration, max_velocity) = if boosted {
+                    (physical.acceleration * BOOST_ACCELERATION, physical.max_velocity * BOOST_MAX_VELOCITY)
                 } else {
-                    None
-                });
-
-                let thrust_entity = thrust_entity.unwrap();
+                    (physical.acceleration, physical.max_velocity)
+                };
 
-                if thrust > 0.0 {
-                    let added = math::Vector3::y() * physical.acceleration * time.delta_seconds() * thrust;
-                    let added = transform.rotation() * added;
-                    physical.velocity += math::Vector2::new(added.x, added.y);
+                let added = math::Vector3::y() * acceleration * time.delta_seconds() * thrust;
+                let added = transform.rotation() * added;
+                physical.velocity += math::Vector2::new(added.x, added.y);
 
-                    // limit velocity
-                    let magnitude = physical.velocity.magnitude();
-
-                    if magnitude > physical.max_velocity {
-                        physical.velocity /= magnitude / physical.max_velocity;
-                    }
+                // limit velocity
+                let magnitude = physical.velocity.magnitude();
 
-                    // Remove Hidden tag
-                    hidden_entities.remove(thrust_entity);
+                if magnitude > max_velocity {
+                    physical.velocity /= magnitude / max_velocity;
+                }
 
-                    // Timer for basic sound effects
-                    if ship.thrust_timer <= 0.0 {
-                        // play SFX
-                        play_thrust_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
-                        ship.thrust_timer = 0.2;
+                // Remove Hidden tag, unless the ship has cloaked mid-burn.
+                // A ship that has lost its thrust child still flies, it just shows no flame.
+                if let Some(thrust_entity) = thrust_entity {
+                    if cloaked {
+                        hidden_entities.insert(thrust_entity, Hidden).expect("Unable to hide thrust of cloaked ship");
                     } else {
-                        ship.thrust_timer -= time.delta_seconds();
+                        hidden_entities.remove(thrust_entity);
                     }
+                }
 
+                // exhaust billows harder under boost
+                if let Some(emitter) = emitter {
+                    emitter.intensity = if boosted { thrust * BOOST_ACCELERATION } else { thrust };
+                }
 
+                // Timer for basic sound effects
+                if ship.thrust_timer <= 0.0 {
+                    // play SFX
+                    play_thrust_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+                    ship.thrust_timer = 0.2;
                 } else {
-                    // No thrust - add hidden tag
-                    hidden_entities.insert(thrust_entity, Hidden).expect("");
+                    ship.thrust_timer -= time.delta_seconds();
+                }
+
+
+            } else {
+                // No thrust - add hidden tag
+                if let Some(thrust_entity) = thrust_entity {
+                    hidden_entities.insert(thrust_entity, Hidden).expect("Unable to hide ship thrust");
+                }
+
+                if let Some(emitter) = emitter {
+                    emitter.intensity = 0.0;
                 }
             }
         }
@@ -0,0 +1,142 @@
+use amethyst::{
+    core::{math::{Vector2, Vector3}, Hidden, SystemDesc, Time, Transform},
+    derive::SystemDesc,
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::{palette::Srgba, resources::Tint},
+};
+
+use crate::components::{Energy, Physical, Ship, ShipControl, Side, Special, SpecialType};
+use crate::resources::ArenaDefinition;
+
+const BLINK_DISTANCE: f32 = 250.0;
+// acceleration applied to the enemy while the tractor beam is on
+const TRACTOR_PULL: f32 = 3.0;
+// afterburner sets velocity to this multiple of the ship's max velocity
+const AFTERBURNER_BOOST: f32 = 2.0;
+
+/// Triggers special abilities and applies their ongoing effects
+#[derive(SystemDesc)]
+pub struct SpecialSystem;
+
+impl<'s> System<'s> for SpecialSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, ShipControl>,
+        WriteStorage<'s, Special>,
+        WriteStorage<'s, Energy>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Physical>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Tint>,
+        Read<'s, ArenaDefinition>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (
+        entities,
+        ships,
+        controls,
+        mut specials,
+        mut energies,
+        mut transforms,
+        mut physicals,
+        mut hidden,
+        mut tints,
+        arena,
+        time,
+    ): Self::SystemData) {
+
+        // ships pulling with a tractor beam this frame
+        let mut tractors: Vec<(Side, f32, f32)> = Vec::new();
+
+        for (entity, ship, control, special, energy, transform, physical) in (
+            &entities,
+            &ships,
+            &controls,
+            &mut specials,
+            &mut energies,
+            &mut transforms,
+            &mut physicals,
+        ).join() {
+
+            if special.tick(time.delta_seconds()) {
+                end_effect(special.special_type, entity, &mut hidden, &mut tints);
+            }
+
+            if control.special && special.is_ready() && energy.try_consume(energy.special_cost) {
+                special.activate();
+
+                let forward = transform.rotation() * Vector3::y();
+
+                match special.special_type {
+                    SpecialType::Blink => {
+                        transform.prepend_translation(forward * BLINK_DISTANCE);
+                    }
+                    SpecialType::Cloak => {
+                        hidden.insert(entity, Hidden).expect("Unable to cloak ship");
+                    }
+                    SpecialType::Shield => {
+                        tints.insert(entity, shield_tint())
+                            .expect("Unable to tint shielded ship");
+                    }
+                    SpecialType::Afterburner => {
+                        let burst = forward * physical.max_velocity * AFTERBURNER_BOOST;
+                        physical.velocity = Vector2::new(burst.x, burst.y);
+                    }
+                    SpecialType::Tractor => {}
+                }
+            }
+
+            if special.is_active() && special.special_type == SpecialType::Tractor {
+                tractors.push((ship.side, transform.translation().x, transform.translation().y));
+            }
+        }
+
+        // tractor beams pull every enemy ship toward the puller
+        for (side, x, y) in tractors {
+            for (ship, transform, physical) in (&ships, &transforms, &mut physicals).join() {
+                if ship.side == side {
+                    continue;
+                }
+
+                let (dx, dy) = arena.state().delta(transform.translation().x, transform.translation().y, x, y);
+                let pull = Vector2::new(dx, dy);
+
+                if pull.magnitude() > 0.0 {
+                    physical.velocity += pull.normalize() * TRACTOR_PULL * time.delta_seconds();
+
+                    // limit velocity, so a long pull cannot fling the enemy past its top speed
+                    let magnitude = physical.velocity.magnitude();
+
+                    if magnitude > physical.max_velocity {
+                        physical.velocity /= magnitude / physical.max_velocity;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Blue glow over a ship whose Shield special is running
+pub fn shield_tint() -> Tint {
+    Tint(Srgba::new(0.4, 0.8, 1.0, 1.0))
+}
+
+/// Undoes the visual state of an effect that has run out
+pub fn end_effect(
+    special_type: SpecialType,
+    entity: Entity,
+    hidden: &mut WriteStorage<Hidden>,
+    tints: &mut WriteStorage<Tint>,
+) {
+    match special_type {
+        SpecialType::Cloak => {
+            hidden.remove(entity);
+        }
+        SpecialType::Shield => {
+            tints.remove(entity);
+        }
+        _ => {}
+    }
+}
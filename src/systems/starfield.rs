@@ -0,0 +1,55 @@
+use amethyst::{
+    core::{Transform, SystemDesc, math::Vector3},
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
+    renderer::Camera,
+};
+
+use crate::components::Parallax;
+use crate::resources::ArenaDefinition;
+
+/// Moves the starfield, nebulae and arena backdrop with the camera so that deeper layers
+/// drift past more slowly, and keeps far layers from swelling as the camera zooms in.
+/// Background sprites have no Physical, so nothing here takes part in collisions.
+#[derive(SystemDesc)]
+pub struct StarfieldSystem;
+
+impl<'s> System<'s> for StarfieldSystem {
+    type SystemData = (
+        ReadStorage<'s, Parallax>,
+        ReadStorage<'s, Camera>,
+        WriteStorage<'s, Transform>,
+        Read<'s, ArenaDefinition>,
+    );
+
+    fn run(&mut self, (parallaxes, cameras, mut transforms, arena): Self::SystemData) {
+        let (camera_x, camera_y, zoom) = match (&cameras, &transforms).join().next() {
+            Some((_, transform)) => (transform.translation().x, transform.translation().y, transform.scale().x),
+            None => return,
+        };
+
+        let (centre_x, centre_y) = (arena.width / 2.0, arena.height / 2.0);
+
+        for (parallax, transform) in (&parallaxes, &mut transforms).join() {
+            let (x, y) = match parallax.tile {
+                // repeat around the camera, sliding by the layer's share of the camera's movement
+                Some((tile_width, tile_height)) => (
+                    camera_x + (parallax.x - camera_x * parallax.depth).rem_euclid(tile_width) - tile_width / 2.0,
+                    camera_y + (parallax.y - camera_y * parallax.depth).rem_euclid(tile_height) - tile_height / 2.0,
+                ),
+                // in place with the camera at the centre of the arena
+                None => (
+                    parallax.x + (camera_x - centre_x) * (1.0 - parallax.depth),
+                    parallax.y + (camera_y - centre_y) * (1.0 - parallax.depth),
+                ),
+            };
+
+            // deeper layers keep more of their on-screen size as the camera zooms
+            let scale = parallax.scale * (parallax.depth + (1.0 - parallax.depth) * zoom);
+
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
+            transform.set_scale(Vector3::new(scale, scale, 1.0));
+        }
+    }
+}
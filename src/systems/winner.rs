@@ -1,16 +1,19 @@
 use amethyst::{
     core::transform::Transform,
-    core::SystemDesc,
+    core::{Hidden, SystemDesc},
     core::math::Vector2,
     derive::SystemDesc,
-    ui::UiText,
-    ecs::prelude::{Join, System, SystemData, World, Write, WriteStorage, ReadStorage, ReadExpect, Entities},
+    renderer::resources::Tint,
+    ecs::prelude::{Join, System, SystemData, World, Read, Write, WriteStorage, ReadStorage, Entities},
 };
 
-use crate::states::{ARENA_WIDTH, ARENA_HEIGHT};
-use crate::components::{Ship, Side, Physical, Combat, Laser};
-use crate::components::{ScoreBoard, ScoreText, StructureText};
+use crate::resources::{ArenaDefinition, CombatLog, Kill, Wreck};
+use crate::components::{Ship, Side, Physical, Combat, Laser, Energy, Special, Shield};
+use crate::systems::special::end_effect;
+use crate::components::{ScoreBoard, DangerZone};
 
+/// Scores destroyed ships and starts the next round. The wreckage and screen shake are left
+/// to the ParticleSystem and ImpactSystem, which find each destroyed ship in the CombatLog.
 #[derive(SystemDesc)]
 pub struct WinnerSystem;
 
@@ -22,57 +25,65 @@ impl<'s> System<'s> for WinnerSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Physical>,
         WriteStorage<'s, Combat>,
-
-        WriteStorage<'s, UiText>,
-        ReadExpect<'s, StructureText>,
+        WriteStorage<'s, Energy>,
+        WriteStorage<'s, Special>,
+        WriteStorage<'s, Shield>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Tint>,
 
         Write<'s, ScoreBoard>,
-        ReadExpect<'s, ScoreText>,
+        Read<'s, ArenaDefinition>,
+        Write<'s, DangerZone>,
+        Write<'s, CombatLog>,
     );
 
-    fn run(&mut self, (entities, ships, lasers, mut locals, mut physicals, mut combats, mut ui_text, struct_text, mut scores, score_text): Self::SystemData) {
+    fn run(&mut self, (entities, ships, lasers, mut locals, mut physicals, mut combats, mut energies, mut specials, mut shields, mut hidden, mut tints, mut scores, arena, mut zone, mut combat_log): Self::SystemData) {
 
-        let mut is_destroyed: bool;
+        combat_log.wrecks.clear();
 
-        for (entity, ship) in (&entities, &ships).join() {
+        // every side with a ship in the fight
+        let mut sides: Vec<Side> = Vec::new();
+        for ship in (&ships).join() {
+            if !sides.contains(&ship.side) {
+                sides.push(ship.side);
+            }
+        }
 
-            let structure = combats.get(entity).unwrap().structure;
+        for (entity, ship) in (&entities, &ships).join() {
 
-            is_destroyed = match ship.side {
-                Side::Light => {
+            let is_destroyed = combats.get(entity).map_or(false, |combat| combat.structure <= 0);
 
-                    if structure <= 0 {
-                        // Right player scores
-                        scores.score_dark = (scores.score_dark + 1)
-                            .min(999);
+            if is_destroyed {
+                // every other side scores
+                for side in sides.iter().filter(|side| **side != ship.side) {
+                    scores.add_point(*side);
+                }
 
-                        if let Some(text) = ui_text.get_mut(score_text.dark_text) {
-                            text.text = scores.score_dark.to_string();
-                        }
-                        // Return true
-                        true
-                    } else {
-                        false
-                    }
+                // credit the kill to whatever hit the ship last
+                if let Some((weapon, attacker)) = combat_log.last_hit(entity) {
+                    let killer = attacker.and_then(|side| {
+                        (&ships).join()
+                            .find(|other| other.side == side)
+                            .map(|other| (other.name.clone(), other.side))
+                    });
+
+                    combat_log.kill(Kill {
+                        victim: (ship.name.clone(), ship.side),
+                        killer,
+                        weapon,
+                    });
                 }
-                Side::Dark => {
-                    if structure <= 0 {
-                        // Left player scores
-                        scores.score_light = (scores.score_light + 1)
-                            .min(999);
-
-                        if let Some(text) = ui_text.get_mut(score_text.light_text) {
-                            text.text = scores.score_light.to_string();
-                        }
-                        // return true
-                        true
-                    } else {
-                        false
-                    }
+                combat_log.new_round();
+
+                // note where the ship blew up, before it is sent back to its spawn point
+                if let (Some(transform), Some(physical)) = (locals.get(entity), physicals.get(entity)) {
+                    combat_log.wrecks.push(Wreck {
+                        x: transform.translation().x,
+                        y: transform.translation().y,
+                        velocity: physical.velocity,
+                    });
                 }
-            };
 
-            if is_destroyed {
                 // reset physics destroy lasers and reposition ships
 
                 // destroy lasers
@@ -80,50 +91,35 @@ impl<'s> System<'s> for WinnerSystem {
                     entities.delete(entity).expect("Failed to delete laser");
                 }
 
-                // Correctly position the ships.
-                let y = ARENA_HEIGHT / 2.0;
-    
-                for (ship, transform, physical, combat) in (&ships, &mut locals, &mut physicals, &mut combats).join() {
-                    match ship.side {
-                        Side::Light => {
-                            transform.set_translation_xyz(physical.radius * 4.0, y, 0.0);
-                            physical.velocity = Vector2::new(0.0, 0.0);
-                            physical.rotation = 0.0;
-                            // rotate ships
-                            transform.rotate_2d(1.60);
-                            combat.structure = 150;
-                        }
-                        Side::Dark => {
-                            transform.set_translation_xyz(ARENA_WIDTH - physical.radius * 4.0, y, 0.0);
-                            physical.velocity = Vector2::new(0.0, 0.0);
-                            physical.rotation = 0.0;
-                            // rotate ships
-                            transform.rotate_2d(-1.60);
-                            combat.structure = 150;
-                        }
+                // reopen the danger zone
+                *zone = DangerZone::default();
+
+                // Return the ships to the arena's spawn points.
+                for (entity, ship, transform, physical, combat, energy, special, shield) in (&entities, &ships, &mut locals, &mut physicals, &mut combats, &mut energies, (&mut specials).maybe(), (&mut shields).maybe()).join() {
+                    energy.refill();
+
+                    if let Some(shield) = shield {
+                        shield.refill();
+                    }
+
+                    // clear cooldowns and any cloak or shield still running
+                    if let Some(special) = special {
+                        end_effect(special.special_type, entity, &mut hidden, &mut tints);
+                        special.reset();
                     }
+
+                    let spawn = arena.spawn(ship.side);
+                    transform.set_translation_xyz(spawn.x, spawn.y, 0.0);
+                    transform.set_rotation_2d(spawn.rotation);
+                    physical.velocity = Vector2::new(0.0, 0.0);
+                    physical.rotation = 0.0;
+                    combat.structure = combat.max_structure;
                 }
                 println!(
                     "Score: | {:^3} | {:^3} |",
-                    scores.score_light, scores.score_dark
+                    scores.score(Side::Light), scores.score(Side::Dark)
                 );
             }
-
-            // Update HP tracker
-            match ship.side {
-                Side::Light => {
-                    let combat = combats.get(entity).unwrap();
-                    if let Some(text) = ui_text.get_mut(struct_text.light_struct_text) {
-                        text.text = format!("HP: {}", combat.structure);
-                    }
-                }
-                Side::Dark => {
-                    let combat = combats.get(entity).unwrap();
-                    if let Some(text) = ui_text.get_mut(struct_text.dark_struct_text) {
-                        text.text = format!("HP: {}", combat.structure);
-                    }
-                }
-            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,555 @@
+//! `paladin validate-assets`: checks every asset the game refers to without opening a window,
+//! so broken files and references are caught before they show up as placeholders in a match.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use amethyst::config::Config;
+
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+use crate::components::DAMAGE_TIERS;
+use crate::resources::{pack_asset_dir, unknown_ids, ArenaDefinition, Arenas, AssetManifest, ModPacks,
+    ShipDefinition, ShipDefinitions, ARENA_DIR, MANIFEST_FILE, PLACEHOLDER_SPRITE_SHEET, PLACEHOLDER_TEXTURE, SHIP_DIR};
+
+/// Directory under `assets` that UI layouts are loaded from
+const UI_DIR: &str = "ui";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const OGG_SIGNATURE: &[u8] = b"OggS";
+
+// more sprites than any sheet needs, so a corrupt count is reported rather than expanded
+const MAX_SPRITES: u32 = 4096;
+
+/// Something wrong with one asset file
+#[derive(Debug)]
+pub struct Problem {
+    pub file: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+// The parts of amethyst's sprite sheet and prefab formats that are checked. Anything
+// else in the files is skipped over.
+
+#[derive(Deserialize)]
+enum SpriteSheetFile {
+    List(SpriteListFile),
+    Grid(SpriteGridFile),
+}
+
+#[derive(Deserialize)]
+struct SpriteListFile {
+    texture_width: u32,
+    texture_height: u32,
+    sprites: Vec<SpriteRect>,
+}
+
+#[derive(Deserialize)]
+struct SpriteGridFile {
+    texture_width: u32,
+    texture_height: u32,
+    columns: u32,
+    #[serde(default)]
+    rows: Option<u32>,
+    #[serde(default)]
+    sprite_count: Option<u32>,
+    #[serde(default)]
+    cell_size: Option<(u32, u32)>,
+    #[serde(default)]
+    position: (u32, u32),
+}
+
+#[derive(Clone, Deserialize)]
+struct SpriteRect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl SpriteSheetFile {
+    /// The texture size the sheet expects, and the rectangle of every sprite in it.
+    /// A grid too large to lay out is an error rather than an overflow.
+    fn sprites(&self) -> Result<(u32, u32, Vec<SpriteRect>), String> {
+        match self {
+            SpriteSheetFile::List(list) => Ok((
+                list.texture_width,
+                list.texture_height,
+                list.sprites.clone(),
+            )),
+            SpriteSheetFile::Grid(grid) => {
+                let columns = grid.columns.max(1);
+                let rows = grid.rows
+                    .or_else(|| grid.sprite_count.map(|count| count / columns + (count % columns).min(1)))
+                    .unwrap_or(1)
+                    .max(1);
+                let (width, height) = grid.cell_size
+                    .unwrap_or((grid.texture_width / columns, grid.texture_height / rows));
+                let count = match grid.sprite_count.or_else(|| columns.checked_mul(rows)) {
+                    Some(count) if count <= MAX_SPRITES => count,
+                    _ => return Err(format!("grid of more than {} sprites", MAX_SPRITES)),
+                };
+
+                let mut sprites = Vec::with_capacity(count as usize);
+                for i in 0..count {
+                    let x = (i % columns).checked_mul(width).and_then(|x| x.checked_add(grid.position.0));
+                    let y = (i / columns).checked_mul(height).and_then(|y| y.checked_add(grid.position.1));
+
+                    match (x, y) {
+                        (Some(x), Some(y)) => sprites.push(SpriteRect { x, y, width, height }),
+                        _ => return Err("grid runs past the largest possible texture".to_string()),
+                    }
+                }
+                Ok((grid.texture_width, grid.texture_height, sprites))
+            }
+        }
+    }
+}
+
+/// The width and height held in a PNG's IHDR chunk, which always comes first,
+/// or None if `bytes` is not a PNG
+fn png_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || &bytes[..8] != PNG_SIGNATURE || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    // two big endian u32s
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    Some((width, height))
+}
+
+/// What is wrong with sprite `i` of a sheet for a texture of the given size, if anything
+fn sprite_problem(i: usize, sprite: &SpriteRect, texture_width: u32, texture_height: u32) -> Option<String> {
+    let right = sprite.x.checked_add(sprite.width);
+    let bottom = sprite.y.checked_add(sprite.height);
+
+    if sprite.width == 0 || sprite.height == 0 {
+        Some(format!("sprite {} is empty", i))
+    } else if right.map_or(true, |right| right > texture_width) || bottom.map_or(true, |bottom| bottom > texture_height) {
+        Some(format!(
+            "sprite {} ({}, {}, {}x{}) does not fit inside the {}x{} texture",
+            i, sprite.x, sprite.y, sprite.width, sprite.height, texture_width, texture_height
+        ))
+    } else {
+        None
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Prefab")]
+struct PrefabFile {
+    entities: Vec<PrefabEntityFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "PrefabEntity")]
+struct PrefabEntityFile {
+    #[serde(default)]
+    data: Option<AnimationPrefabFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "AnimationPrefabData")]
+struct AnimationPrefabFile {
+    sprite_scene: SpriteSceneFile,
+    animation_set: AnimationSetFile,
+}
+
+#[derive(Deserialize)]
+struct SpriteSceneFile {
+    #[serde(default)]
+    sheet: Option<SheetFile>,
+    #[serde(default)]
+    render: Option<SpriteRenderFile>,
+}
+
+#[derive(Deserialize)]
+enum SheetFile {
+    Sheet {
+        texture: TextureFile,
+        sprites: Vec<SpriteSheetFile>,
+    },
+}
+
+#[derive(Deserialize)]
+enum TextureFile {
+    File(String, IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct SpriteRenderFile {
+    sprite_number: usize,
+}
+
+#[derive(Deserialize)]
+struct AnimationSetFile {
+    animations: Vec<(IgnoredAny, AnimationFile)>,
+}
+
+#[derive(Deserialize)]
+struct AnimationFile {
+    samplers: Vec<(usize, IgnoredAny, SamplerFile)>,
+}
+
+#[derive(Deserialize)]
+struct SamplerFile {
+    input: Vec<f32>,
+    output: Vec<usize>,
+}
+
+/// Collects the problems found while walking the assets
+struct Validator<'a> {
+    assets_dir: &'a Path,
+    problems: Vec<Problem>,
+}
+
+impl<'a> Validator<'a> {
+    fn problem(&mut self, file: &str, message: String) {
+        self.problems.push(Problem { file: file.to_string(), message });
+    }
+
+    fn read(&mut self, file: &str) -> Option<Vec<u8>> {
+        match fs::read(self.assets_dir.join(file)) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                self.problem(file, format!("unable to read: {}", e));
+                None
+            }
+        }
+    }
+
+    fn read_ron<T: for<'de> Deserialize<'de>>(&mut self, file: &str) -> Option<T> {
+        let bytes = self.read(file)?;
+        self.parse_ron(file, &bytes)
+    }
+
+    fn parse_ron<T: for<'de> Deserialize<'de>>(&mut self, file: &str, bytes: &[u8]) -> Option<T> {
+        match ron::de::from_bytes(bytes) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.problem(file, format!("unable to parse: {}", e));
+                None
+            }
+        }
+    }
+
+    /// The width and height of a texture, if it is a PNG. Other images are only checked to exist.
+    fn texture_size(&mut self, file: &str) -> Option<(u32, u32)> {
+        let bytes = self.read(file)?;
+
+        if !file.ends_with(".png") {
+            return None;
+        }
+        let size = png_size(&bytes);
+        if size.is_none() {
+            self.problem(file, "not a valid PNG image".to_string());
+        }
+        size
+    }
+
+    /// Checks the sheet matches its texture and every sprite fits inside it. Returns the
+    /// number of sprites so callers can check the frames they use.
+    fn check_sheet(&mut self, file: &str, texture: &str, sheet: &SpriteSheetFile) -> usize {
+        let (texture_width, texture_height, sprites) = match sheet.sprites() {
+            Ok(sprites) => sprites,
+            Err(e) => {
+                self.problem(file, e);
+                return 0;
+            }
+        };
+
+        if let Some((width, height)) = self.texture_size(texture) {
+            if (width, height) != (texture_width, texture_height) {
+                self.problem(file, format!(
+                    "expects a {}x{} texture but {} is {}x{}",
+                    texture_width, texture_height, texture, width, height
+                ));
+            }
+        }
+
+        for (i, sprite) in sprites.iter().enumerate() {
+            if let Some(problem) = sprite_problem(i, sprite, texture_width, texture_height) {
+                self.problem(file, problem);
+            }
+        }
+
+        sprites.len()
+    }
+
+    /// Checks a sprite sheet ron and its texture, and that it has at least `frames` sprites
+    fn check_sprite_sheet(&mut self, texture: &str, file: &str, frames: usize) {
+        let sheet = match self.read_ron::<SpriteSheetFile>(file) {
+            Some(sheet) => sheet,
+            None => {
+                // still report a missing texture
+                self.texture_size(texture);
+                return;
+            }
+        };
+
+        let count = self.check_sheet(file, texture, &sheet);
+        if count < frames {
+            self.problem(file, format!("has {} sprites but the game draws up to {}", count, frames));
+        }
+    }
+
+    /// Checks an animation prefab's sheet, and that every frame it shows exists
+    fn check_prefab(&mut self, file: &str) {
+        let prefab = match self.read_ron::<PrefabFile>(file) {
+            Some(prefab) => prefab,
+            None => return,
+        };
+
+        for data in prefab.entities.iter().filter_map(|entity| entity.data.as_ref()) {
+            let sprite_count = match data.sprite_scene.sheet.as_ref() {
+                Some(SheetFile::Sheet { texture: TextureFile::File(texture, _), sprites }) => {
+                    let mut count = 0;
+                    for sheet in sprites {
+                        count += self.check_sheet(file, texture, sheet);
+                    }
+                    count
+                }
+                None => continue,
+            };
+
+            if let Some(render) = data.sprite_scene.render.as_ref() {
+                if render.sprite_number >= sprite_count {
+                    self.problem(file, format!(
+                        "renders sprite {} but the sheet has {}", render.sprite_number, sprite_count
+                    ));
+                }
+            }
+
+            for (_, animation) in data.animation_set.animations.iter() {
+                for (_, _, sampler) in animation.samplers.iter() {
+                    if sampler.input.len() != sampler.output.len() {
+                        self.problem(file, format!(
+                            "animation has {} key frame times but {} frames",
+                            sampler.input.len(), sampler.output.len()
+                        ));
+                    }
+                    if sampler.input.windows(2).any(|pair| pair[1] <= pair[0]) {
+                        self.problem(file, "animation key frame times must increase".to_string());
+                    }
+                    for frame in sampler.output.iter().filter(|frame| **frame >= sprite_count) {
+                        self.problem(file, format!(
+                            "animation shows sprite {} but the sheet has {}", frame, sprite_count
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_sound(&mut self, file: &str) {
+        if let Some(bytes) = self.read(file) {
+            if !bytes.starts_with(OGG_SIGNATURE) {
+                self.problem(file, "not an ogg file".to_string());
+            }
+        }
+    }
+
+    /// Checks a UI layout parses, and that the fonts and images it loads exist
+    fn check_ui(&mut self, file: &str) {
+        let bytes = match self.read(file) {
+            Some(bytes) => bytes,
+            None => return,
+        };
+        if self.parse_ron::<IgnoredAny>(file, &bytes).is_none() {
+            return;
+        }
+
+        let contents = String::from_utf8_lossy(&bytes);
+        for reference in contents.split("File(\"").skip(1).filter_map(|rest| rest.split('"').next()) {
+            if !self.assets_dir.join(reference).is_file() {
+                self.problem(file, format!("refers to missing file {}", reference));
+            }
+        }
+    }
+
+    /// Checks that every ship definition in `dir` parses
+    fn check_ships(&mut self, dir: &str) {
+        for file in self.ron_files(dir) {
+            if let Err(e) = ShipDefinition::load(self.assets_dir.join(&file)) {
+                self.problem(&file, format!("unable to parse: {}", e));
+            }
+        }
+    }
+
+    /// Checks that every arena definition in `dir` parses
+    fn check_arenas(&mut self, dir: &str) {
+        for file in self.ron_files(dir) {
+            if let Err(e) = ArenaDefinition::load(self.assets_dir.join(&file)) {
+                self.problem(&file, format!("unable to parse: {}", e));
+            }
+        }
+    }
+
+    /// Every `.ron` file in `dir`, relative to the assets directory
+    fn ron_files(&mut self, dir: &str) -> Vec<String> {
+        let entries = match fs::read_dir(self.assets_dir.join(dir)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.problem(dir, format!("unable to read directory: {}", e));
+                return Vec::new();
+            }
+        };
+
+        let mut files: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".ron"))
+            .map(|name| format!("{}/{}", dir, name))
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// Checks every asset in `assets_dir` that the game loads, returning everything wrong with them.
+/// `manifest`, `ship_definitions` and `arenas` are what loaded with the enabled content packs applied,
+/// so files and ids from the packs are checked too, and the definitions of each enabled pack in
+/// `mod_packs` are parse checked like the base game's.
+pub fn validate_assets(
+    assets_dir: &Path,
+    manifest: &AssetManifest,
+    ship_definitions: &ShipDefinitions,
+    arenas: &Arenas,
+    mod_packs: &ModPacks,
+) -> Vec<Problem> {
+    let mut validator = Validator { assets_dir, problems: Vec::new() };
+
+    // the game carries on with an empty manifest, so a parse error is only caught here
+    if let Err(e) = AssetManifest::load(assets_dir.join(MANIFEST_FILE)) {
+        validator.problem(MANIFEST_FILE, format!("unable to parse: {}", e));
+    }
+
+    // ships switch to a more damaged sprite at each tier
+    let ship_sprite_sheets: Vec<&str> = ship_definitions.definitions()
+        .map(|definition| definition.sprite_sheet.as_str())
+        .collect();
+
+    validator.check_sprite_sheet(PLACEHOLDER_TEXTURE, PLACEHOLDER_SPRITE_SHEET, DAMAGE_TIERS.len() + 1);
+    for (id, entry) in manifest.sprite_sheets.iter() {
+        let frames = if ship_sprite_sheets.contains(&id.as_str()) { DAMAGE_TIERS.len() + 1 } else { 1 };
+        validator.check_sprite_sheet(&entry.texture, &entry.sprite_sheet, frames);
+    }
+    for file in manifest.prefabs.values() {
+        validator.check_prefab(file);
+    }
+    for file in manifest.sounds.values() {
+        validator.check_sound(file);
+    }
+
+    for error in unknown_ids(manifest, ship_definitions, arenas) {
+        validator.problem(MANIFEST_FILE, error.to_string());
+    }
+
+    // definitions that fail to parse are skipped by the game, so are only caught here
+    validator.check_ships(SHIP_DIR);
+    validator.check_arenas(ARENA_DIR);
+
+    for pack in mod_packs.enabled() {
+        let pack_dir = pack_asset_dir(&pack.id);
+
+        // a pack manifest that fails to parse is left out of the game entirely
+        let pack_manifest = format!("{}/{}", pack_dir, MANIFEST_FILE);
+        if assets_dir.join(&pack_manifest).exists() {
+            if let Err(e) = AssetManifest::load(assets_dir.join(&pack_manifest)) {
+                validator.problem(&pack_manifest, format!("unable to parse: {}", e));
+            }
+        }
+
+        // packs only need the directories they add content to
+        let pack_ships = format!("{}/{}", pack_dir, SHIP_DIR);
+        if assets_dir.join(&pack_ships).is_dir() {
+            validator.check_ships(&pack_ships);
+        }
+        let pack_arenas = format!("{}/{}", pack_dir, ARENA_DIR);
+        if assets_dir.join(&pack_arenas).is_dir() {
+            validator.check_arenas(&pack_arenas);
+        }
+    }
+
+    for file in validator.ron_files(UI_DIR) {
+        validator.check_ui(&file);
+    }
+
+    validator.problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(columns: u32, rows: Option<u32>, sprite_count: Option<u32>) -> SpriteSheetFile {
+        SpriteSheetFile::Grid(SpriteGridFile {
+            texture_width: 256,
+            texture_height: 128,
+            columns,
+            rows,
+            sprite_count,
+            cell_size: None,
+            position: (0, 0),
+        })
+    }
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> SpriteRect {
+        SpriteRect { x, y, width, height }
+    }
+
+    #[test]
+    fn grids_expand_row_by_row() {
+        let (width, height, sprites) = grid(4, Some(2), None).sprites().unwrap();
+
+        assert_eq!((width, height), (256, 128));
+        assert_eq!(sprites.len(), 8);
+        // cells are the texture split evenly, numbered across each row
+        assert_eq!((sprites[1].x, sprites[1].y, sprites[1].width, sprites[1].height), (64, 0, 64, 64));
+        assert_eq!((sprites[5].x, sprites[5].y), (64, 64));
+    }
+
+    #[test]
+    fn grids_take_their_rows_from_the_sprite_count() {
+        let (_, _, sprites) = grid(4, None, Some(6)).sprites().unwrap();
+
+        assert_eq!(sprites.len(), 6);
+        assert_eq!((sprites[5].x, sprites[5].y, sprites[5].height), (64, 64, 64));
+    }
+
+    #[test]
+    fn huge_grids_are_reported() {
+        assert!(grid(4, None, Some(u32::MAX)).sprites().is_err());
+        assert!(grid(u32::MAX, Some(u32::MAX), None).sprites().is_err());
+    }
+
+    #[test]
+    fn png_size_reads_the_header() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend_from_slice(&[0, 0, 0, 13]);
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&512u32.to_be_bytes());
+        png.extend_from_slice(&320u32.to_be_bytes());
+
+        assert_eq!(png_size(&png), Some((512, 320)));
+        assert_eq!(png_size(b"GIF89a, not a png at all"), None);
+        assert_eq!(png_size(&png[..20]), None);
+    }
+
+    #[test]
+    fn sprites_must_fit_their_texture() {
+        assert_eq!(sprite_problem(0, &rect(0, 0, 64, 64), 128, 128), None);
+        assert!(sprite_problem(1, &rect(100, 0, 64, 64), 128, 128).is_some());
+        assert!(sprite_problem(2, &rect(0, 0, 0, 64), 128, 128).is_some());
+        // too far out to add up is out of bounds, not a panic
+        assert!(sprite_problem(3, &rect(u32::MAX, 0, 64, 64), 128, 128).is_some());
+    }
+}
\ No newline at end of file